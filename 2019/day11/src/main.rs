@@ -2,9 +2,6 @@
 // it is implemented in the crate `../intcode`.
 extern crate intcode;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::str;
 use std::time::Instant;
 
 fn emergency_hull_painting_robot(
@@ -20,44 +17,36 @@ fn emergency_hull_painting_robot(
     let mut y = 0;
     let mut d = 0;
 
-    let (_, input_tx, output_rx, result_rx) = intcode::compute_threaded(m, None, None, None);
-    let input_tx = input_tx.unwrap();
-    let output_rx = output_rx.unwrap();
-    let result_rx = result_rx.unwrap();
+    let mut robot = intcode::Intcode::new(m);
+    robot.push_input(start);
 
-    input_tx.send(start).unwrap();
-
-    while result_rx.try_recv().is_err() {
-        if let Ok(paint) = output_rx.try_recv() {
-            d = match output_rx.recv().unwrap() {
-                0 => (d - 1) % 4, // left
-                1 => (d + 1) % 4, // right
-                _ => panic!("Unknown direction"),
-            };
-            if d < 0 {
-                d += 4;
-            }
-            painted_panels.insert((x, y), paint);
-            match d {
-                0 => y += 1, // up
-                1 => x += 1, // right
-                2 => y -= 1, // down
-                3 => x -= 1, // left
-                d => panic!(format!("Unknown direction '{}'", d)),
-            }
-            input_tx
-                .send(*painted_panels.get(&(x, y)).unwrap_or(&0))
-                .unwrap();
-            if x > max_x {
-                max_x = x;
-            } else if x < min_x {
-                min_x = x;
-            }
-            if y > max_y {
-                max_y = y;
-            } else if y < min_y {
-                min_y = y;
-            }
+    while let intcode::State::Output(paint) = robot.run().unwrap() {
+        d = match robot.run().unwrap() {
+            intcode::State::Output(0) => (d - 1) % 4, // left
+            intcode::State::Output(1) => (d + 1) % 4, // right
+            s => panic!("Unknown direction '{:?}'", s),
+        };
+        if d < 0 {
+            d += 4;
+        }
+        painted_panels.insert((x, y), paint);
+        match d {
+            0 => y += 1, // up
+            1 => x += 1, // right
+            2 => y -= 1, // down
+            3 => x -= 1, // left
+            d => panic!("Unknown direction '{}'", d),
+        }
+        robot.push_input(*painted_panels.get(&(x, y)).unwrap_or(&0));
+        if x > max_x {
+            max_x = x;
+        } else if x < min_x {
+            min_x = x;
+        }
+        if y > max_y {
+            max_y = y;
+        } else if y < min_y {
+            min_y = y;
         }
     }
 
@@ -67,16 +56,7 @@ fn emergency_hull_painting_robot(
 fn main() {
     let s1 = Instant::now();
 
-    let m = BufReader::new(File::open("./input").unwrap())
-        .split(b',')
-        .map(|ops| {
-            str::from_utf8(&ops.unwrap())
-                .unwrap()
-                .trim()
-                .parse::<i64>()
-                .unwrap()
-        })
-        .collect::<Vec<i64>>();
+    let m = intcode::Program::from_input().unwrap().into_memory();
 
     let (painted_panels, _, _, _, _) = emergency_hull_painting_robot(m.clone(), 0);
 
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use aoc_util::cycle::{find_cycle, Cycle};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::time::Instant;
@@ -37,10 +37,25 @@ fn simulate_universe_step(constellations: Vec<[i16; 6]>) -> Vec<[i16; 6]> {
         .collect()
 }
 
+// Every axis evolves independently of the others
+fn simulate_axis_step(moons: &Vec<(i16, i16)>) -> Vec<(i16, i16)> {
+    moons
+        .iter()
+        .map(|&(position, velocity)| {
+            let velocity = velocity
+                + moons
+                    .iter()
+                    .map(|&(other_position, _)| (other_position - position).signum())
+                    .sum::<i16>();
+            (position + velocity, velocity)
+        })
+        .collect()
+}
+
 fn main() {
     let s1 = Instant::now();
 
-    let mut constellations: Vec<[i16; 6]> = BufReader::new(File::open("./input").unwrap())
+    let constellations: Vec<[i16; 6]> = BufReader::new(File::open("./input").unwrap())
         .lines()
         .map(|l| {
             let xyz = l
@@ -71,7 +86,19 @@ fn main() {
 
     let s2 = Instant::now();
 
-    println!("part2: TODO, ({}s)", s2.elapsed().as_secs());
+    let universe_cycle = Cycle::combine((0..3).map(|axis| {
+        let moons = constellations
+            .iter()
+            .map(|moon| (moon[axis], moon[axis + 3]))
+            .collect::<Vec<_>>();
+        find_cycle(&moons, simulate_axis_step)
+    }));
+
+    println!(
+        "part2: {}, ({}µs)",
+        universe_cycle.first_repeat(),
+        s2.elapsed().as_micros()
+    );
 
-    println!("time: {}s", s1.elapsed().as_secs());
+    println!("time: {}µs", s1.elapsed().as_micros());
 }
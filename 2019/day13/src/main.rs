@@ -1,26 +1,14 @@
 // Note: Since the "intcode computer" should be reused later
 // it is implemented in the crate `../intcode`.
 extern crate intcode;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::str;
 use std::time::Instant;
 
 fn main() {
     let s1 = Instant::now();
 
-    let mut m = BufReader::new(File::open("./input").unwrap())
-        .split(b',')
-        .map(|ops| {
-            str::from_utf8(&ops.unwrap())
-                .unwrap()
-                .trim()
-                .parse::<i64>()
-                .unwrap()
-        })
-        .collect::<Vec<i64>>();
+    let mut m = intcode::Program::from_input().unwrap().into_memory();
 
-    let (_, mut output) = intcode::compute(m.clone(), vec![]);
+    let (_, mut output) = intcode::compute(m.clone(), vec![]).unwrap();
 
     let mut counter = 0;
 
@@ -37,47 +25,35 @@ fn main() {
     let s2 = Instant::now();
 
     m[0] = 2;
-    let (_, input_tx, output_rx, result_rx) = intcode::compute_threaded(m, None, None, None);
-    let input_tx = input_tx.unwrap();
-    let output_rx = output_rx.unwrap();
-    let result_rx = result_rx.unwrap();
+    let mut arcade = intcode::Intcode::new(m);
 
-    let mut paddle_pos = None;
-    let mut ball_pos = None;
+    let mut paddle_pos = 0;
+    let mut ball_pos = 0;
     let mut score = 0;
 
-    while result_rx.try_recv().is_err() {
-        if let Ok(x) = output_rx.try_recv() {
-            output_rx.recv().unwrap();
-            let value = output_rx.recv().unwrap();
-            if x == -1 {
-                score = value;
-            } else {
-                match value {
-                    0 | 1 | 2 => {}
-                    3 => {
-                        paddle_pos = Some(x);
+    loop {
+        match arcade.run().unwrap() {
+            intcode::State::Output(x) => {
+                let mut next_output = || match arcade.run().unwrap() {
+                    intcode::State::Output(o) => o,
+                    s => panic!("Expected an output but got '{:?}'", s),
+                };
+                next_output();
+                let value = next_output();
+                if x == -1 {
+                    score = value;
+                } else {
+                    match value {
+                        0 | 1 | 2 => {}
+                        3 => paddle_pos = x,
+                        4 => ball_pos = x,
+                        _ => panic!("Unknown tile id '{}'", value),
                     }
-                    4 => {
-                        ball_pos = Some(x);
-                    }
-                    _ => panic!(format!("Unknown tile id '{}'", value)),
-                }
-            }
-            if let Some(p_pos) = paddle_pos {
-                if let Some(b_pos) = ball_pos {
-                    input_tx
-                        .send(if p_pos < b_pos {
-                            1
-                        } else if p_pos > b_pos {
-                            -1
-                        } else {
-                            0
-                        })
-                        .unwrap();
-                    ball_pos = None;
                 }
             }
+            // Joystick follows the ball
+            intcode::State::NeedsInput => arcade.push_input((ball_pos - paddle_pos).signum()),
+            intcode::State::Halted => break,
         }
     }
     println!("part2: {}, ({}µs)", score, s2.elapsed().as_micros());
@@ -1,105 +1,79 @@
 // Note: Since the "intcode computer" should be reused later
 // it is implemented in the crate `../intcode`.
 extern crate intcode;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::str;
-use std::sync::mpsc::{Receiver, Sender};
+use intcode::{Intcode, Program, State};
+use std::collections::{HashMap, VecDeque};
 use std::time::Instant;
 
 type Map = HashMap<(i64, i64), u8>;
 
-fn traverse(
-    input_tx: &Sender<i64>,
-    output_rx: &Receiver<i64>,
-    previous: Option<&i64>,
-    map: &mut Map,
-    pos: (i64, i64),
-) -> (u64, i64, (i64, i64)) {
-    const OPPOSITE_DIRECTION: [i64; 4] = [2, 1, 4, 3];
-    const XY_DIRECTION: [(i64, i64); 4] = [(0, 1), (0, -1), (-1, 0), (1, 0)];
-    let mut pos = pos;
-
-    for d in [1, 2, 3, 4].iter().filter(|d| {
-        // Don't go back from where we came
-        if let Some(previous) = previous {
-            *d != &OPPOSITE_DIRECTION[(previous - 1) as usize]
-        } else {
-            true
-        }
-    }) {
-        input_tx.send(*d).unwrap();
-        match output_rx.recv().unwrap() {
-            0 => {
-                // hit wall, next direction
-                map.insert(
-                    (
-                        pos.0 + XY_DIRECTION[(d - 1) as usize].0,
-                        pos.1 + XY_DIRECTION[(d - 1) as usize].1,
-                    ),
-                    0,
-                );
+const XY_DIRECTION: [(i64, i64); 4] = [(0, 1), (0, -1), (-1, 0), (1, 0)];
+
+// Breadth first search over droids, every step continues with a clone of the droid before it,
+// so there is no need to walk back. Returns the steps to the oxygen system.
+fn traverse(droid: Intcode, map: &mut Map) -> Option<(u64, (i64, i64))> {
+    let mut oxygen_system = None;
+    let mut queue = VecDeque::new();
+    queue.push_back((droid, (0, 0), 0));
+    map.insert((0, 0), 3);
+
+    while let Some((droid, pos, steps)) = queue.pop_front() {
+        for d in 1..=4 {
+            let new_pos = (
+                pos.0 + XY_DIRECTION[(d - 1) as usize].0,
+                pos.1 + XY_DIRECTION[(d - 1) as usize].1,
+            );
+            if map.contains_key(&new_pos) {
+                continue;
             }
-            1 => {
-                // moved, continue
-                pos.0 += XY_DIRECTION[(d - 1) as usize].0;
-                pos.1 += XY_DIRECTION[(d - 1) as usize].1;
-                map.insert(pos, 1);
-
-                let (steps, result, new_pos) = traverse(&input_tx, &output_rx, Some(d), map, pos);
-                match result {
-                    0 => {
-                        // no way found, reverse step
-                        input_tx.send(OPPOSITE_DIRECTION[(d - 1) as usize]).unwrap();
-                        assert_eq!(output_rx.recv().unwrap(), 1);
-                    }
-                    2 => {
-                        // oxygen system found, return
-                        return (steps + 1, result, new_pos);
-                    }
-                    usc => panic!(format!("Unexpected status code '{}'", usc)),
-                }
+            let mut droid = droid.clone();
+            droid.push_input(d);
+            let status = match droid.run().unwrap() {
+                State::Output(status @ 0..=2) => status as u8,
+                s => panic!("Unexpected status '{:?}'", s),
+            };
+            map.insert(new_pos, status);
+            if status == 2 && oxygen_system.is_none() {
+                oxygen_system = Some((steps + 1, new_pos));
             }
-            2 => {
-                // oxygen system found
-                pos.0 += XY_DIRECTION[(d - 1) as usize].0;
-                pos.1 += XY_DIRECTION[(d - 1) as usize].1;
-                map.insert(pos, 2);
-                return (1, 2, pos);
+            if status != 0 {
+                queue.push_back((droid, new_pos, steps + 1));
+            }
+        }
+    }
+
+    oxygen_system
+}
+
+// Minutes until oxygen spread from the start to every open position of the map
+fn fill(map: &Map, start: (i64, i64)) -> u64 {
+    let mut minutes = HashMap::new();
+    let mut queue = VecDeque::new();
+    minutes.insert(start, 0);
+    queue.push_back(start);
+
+    while let Some(pos) = queue.pop_front() {
+        let minute = minutes[&pos];
+        for (dx, dy) in XY_DIRECTION.iter() {
+            let new_pos = (pos.0 + dx, pos.1 + dy);
+            if map.get(&new_pos).is_some_and(|&p| p != 0) && !minutes.contains_key(&new_pos) {
+                minutes.insert(new_pos, minute + 1);
+                queue.push_back(new_pos);
             }
-            usc => panic!(format!("Unexpected status code '{}'", usc)),
         }
     }
-    // no way found, return
-    (0, 0, pos)
+
+    minutes.values().copied().max().unwrap_or(0)
 }
 
 fn main() {
     let s1 = Instant::now();
 
-    let m = BufReader::new(File::open("./input").unwrap())
-        .split(b',')
-        .map(|ops| {
-            str::from_utf8(&ops.unwrap())
-                .unwrap()
-                .trim()
-                .parse::<i64>()
-                .unwrap()
-        })
-        .collect::<Vec<i64>>();
-
-    let (_, input_tx, output_rx, _) = intcode::compute_threaded(m.clone(), None, None, None);
+    let m = Program::from_input().unwrap().into_memory();
 
     let mut map: Map = HashMap::new();
-    map.insert((0, 0), 3);
-    let (steps, _, _) = traverse(
-        &input_tx.unwrap(),
-        &output_rx.unwrap(),
-        None,
-        &mut map,
-        (0, 0),
-    );
+    let (steps, oxygen_system) =
+        traverse(Intcode::new(m), &mut map).expect("No oxygen system found");
 
     println!("part1: {:?}, ({}µs)", steps, s1.elapsed().as_micros());
 
@@ -115,7 +89,7 @@ fn main() {
                         1 => ".",
                         2 => "o",
                         3 => "S",
-                        usc => panic!(format!("Unexpected map code '{}'", usc)),
+                        usc => panic!("Unexpected map code '{}'", usc),
                     }
                 } else {
                     "?"
@@ -125,7 +99,11 @@ fn main() {
         println!("");
     }
 
-    println!("part2: {}, ({}µs)", 0, s2.elapsed().as_micros());
+    println!(
+        "part2: {}, ({}µs)",
+        fill(&map, oxygen_system),
+        s2.elapsed().as_micros()
+    );
 
     println!("time: {}µs", s1.elapsed().as_micros());
 }
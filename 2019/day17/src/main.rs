@@ -1,69 +1,155 @@
 // Note: Since the "intcode computer" should be reused later
 // it is implemented in the crate `../intcode`.
 extern crate intcode;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::str;
-//use std::sync::mpsc::{Receiver, Sender};
+use intcode::ascii::Ascii;
+use intcode::{Intcode, Program, State};
 use std::time::Instant;
 
-type Map = HashMap<(i64, i64), i64>;
+const DIRECTIONS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const MAX_ROUTINE_LENGTH: usize = 20;
 
-fn main() {
-    let s1 = Instant::now();
+fn is_scaffold(map: &[Vec<u8>], (x, y): (i64, i64)) -> bool {
+    x >= 0
+        && y >= 0
+        && map
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .is_some_and(|&c| c != b'.')
+}
 
-    let m = BufReader::new(File::open("./input").unwrap())
-        .split(b',')
-        .map(|ops| {
-            str::from_utf8(&ops.unwrap())
-                .unwrap()
-                .trim()
-                .parse::<i64>()
-                .unwrap()
+// The whole path as turns followed by the number of steps, e.g. "R,8"
+fn path(map: &[Vec<u8>]) -> Vec<String> {
+    let (mut x, mut y, mut d) = map
+        .iter()
+        .enumerate()
+        .find_map(|(y, row)| {
+            row.iter().enumerate().find_map(|(x, c)| {
+                b"^>v<"
+                    .iter()
+                    .position(|r| r == c)
+                    .map(|d| (x as i64, y as i64, d))
+            })
         })
-        .collect::<Vec<i64>>();
+        .expect("No robot found");
 
-    let mut map: Map = HashMap::new();
+    let mut path = vec![];
+    loop {
+        let (turn, new_d) =
+            match [("R", (d + 1) % 4), ("L", (d + 3) % 4)]
+                .iter()
+                .find(|(_, new_d)| {
+                    is_scaffold(map, (x + DIRECTIONS[*new_d].0, y + DIRECTIONS[*new_d].1))
+                }) {
+                Some(&turn) => turn,
+                None => return path,
+            };
+        d = new_d;
+        let mut steps = 0;
+        while is_scaffold(map, (x + DIRECTIONS[d].0, y + DIRECTIONS[d].1)) {
+            x += DIRECTIONS[d].0;
+            y += DIRECTIONS[d].1;
+            steps += 1;
+        }
+        path.push(format!("{},{}", turn, steps));
+    }
+}
 
-    let mut calibration = 0;
-    let mut x = 0;
-    let mut y = 0;
-    for point in intcode::compute(m.clone(), Vec::new()).1 {
-        map.insert(
-            (x, y),
-            if point == 10 {
-                y += 1;
-                x = 0;
-                continue;
-            } else {
-                if map.get(&(x + 1, y - 1)) == Some(&35)
-                    && map.get(&(x - 1, y - 1)) == Some(&35)
-                    && map.get(&(x, y - 1)) == Some(&35)
-                    && map.get(&(x, y - 2)) == Some(&35)
-                {
-                    calibration += x * (y - 1);
-                }
-                point
-            },
-        );
-        x += 1;
+// Splits the path into a main routine that calls up to three movement functions
+fn compress<'a>(
+    path: &'a [String],
+    functions: &mut Vec<&'a [String]>,
+    main: &mut Vec<usize>,
+) -> bool {
+    if path.is_empty() {
+        return true;
+    }
+    if main.len() == MAX_ROUTINE_LENGTH / 2 {
+        return false;
+    }
+    for f in 0..functions.len() {
+        if path.starts_with(functions[f]) {
+            main.push(f);
+            if compress(&path[functions[f].len()..], functions, main) {
+                return true;
+            }
+            main.pop();
+        }
+    }
+    if functions.len() < 3 {
+        for len in 1..=path.len() {
+            if path[..len].join(",").len() > MAX_ROUTINE_LENGTH {
+                break;
+            }
+            functions.push(&path[..len]);
+            main.push(functions.len() - 1);
+            if compress(&path[len..], functions, main) {
+                return true;
+            }
+            main.pop();
+            functions.pop();
+        }
     }
+    false
+}
+
+fn main() {
+    let s1 = Instant::now();
+
+    let mut m = Program::from_input().unwrap().into_memory();
+
+    let (camera, _) = Ascii::new(Intcode::new(m.clone())).run().unwrap();
+    let map = camera
+        .lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.bytes().collect::<Vec<u8>>())
+        .collect::<Vec<_>>();
 
-    for y in (0..y).rev() {
-        for x in 0..50 {
-            if let Some(point) = map.get(&(x, y)) {
-                print!("{}", (*point as u8) as char);
+    println!("{}", camera.text());
+
+    let mut calibration = 0;
+    for y in 1..map.len() as i64 - 1 {
+        for x in 1..map[y as usize].len() as i64 - 1 {
+            if [(0, 0), (0, -1), (1, 0), (0, 1), (-1, 0)]
+                .iter()
+                .all(|(dx, dy)| is_scaffold(&map, (x + dx, y + dy)))
+            {
+                calibration += x * y;
             }
         }
-        println!("");
     }
 
     println!("part1: {:?}, ({}µs)", calibration, s1.elapsed().as_micros());
 
     let s2 = Instant::now();
 
-    println!("part2: {}, ({}µs)", 0, s2.elapsed().as_micros());
+    let path = path(&map);
+    let mut functions = vec![];
+    let mut main = vec![];
+    if !compress(&path, &mut functions, &mut main) {
+        panic!("Path '{}' can't be compressed", path.join(","));
+    }
+
+    m[0] = 2;
+    let mut robot = Ascii::new(Intcode::new(m));
+    robot.send_line(
+        &main
+            .iter()
+            .map(|&f| ["A", "B", "C"][f])
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    // Functions that aren't called still have to be defined
+    for f in 0..3 {
+        robot.send_line(&functions.get(f).unwrap_or(&functions[0]).join(","));
+    }
+    robot.send_line("n");
+
+    let (output, state) = robot.run().unwrap();
+    assert_eq!(state, State::Halted, "{}", output.text());
+    let dust = output.value().expect("No dust amount reported");
+
+    println!("part2: {}, ({}µs)", dust, s2.elapsed().as_micros());
 
     println!("time: {}µs", s1.elapsed().as_micros());
 }
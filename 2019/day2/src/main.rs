@@ -1,28 +1,16 @@
 // Note: Since the "intcode computer" should be reused later
 // it is implemented in the crate `../intcode`.
 extern crate intcode;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::str;
 use std::time::Instant;
 
 fn main() {
     let s1 = Instant::now();
 
-    let mut m = BufReader::new(File::open("./input").unwrap())
-        .split(b',')
-        .map(|ops| {
-            str::from_utf8(&ops.unwrap())
-                .unwrap()
-                .trim()
-                .parse::<i64>()
-                .unwrap()
-        })
-        .collect::<Vec<i64>>();
+    let mut m = intcode::Program::from_input().unwrap().into_memory();
 
     m[1] = 12;
     m[2] = 2;
-    let (part1_m, _) = intcode::compute(m.clone(), vec![]);
+    let (part1_m, _) = intcode::compute(m.clone(), vec![]).unwrap();
     println!("part1: {}, ({}µs)", part1_m[0], s1.elapsed().as_micros());
 
     let s2 = Instant::now();
@@ -43,9 +31,11 @@ pub fn bruteforce(m: Vec<i64>, expected_nm0: i64) -> Option<(i64, i64)> {
             let mut m = m.clone();
             m[1] = noun;
             m[2] = verb;
-            let (nm, _) = intcode::compute(m, vec![]);
-            if nm[0] == expected_nm0 {
-                return Some((noun, verb));
+            // Some nouns and verbs turn the program into garbage, those are skipped
+            if let Ok((nm, _)) = intcode::compute(m, vec![]) {
+                if nm[0] == expected_nm0 {
+                    return Some((noun, verb));
+                }
             }
         }
     }
@@ -1,26 +1,14 @@
 // Note: Since the "intcode computer" should be reused later
 // it is implemented in the crate `../intcode`.
 extern crate intcode;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::str;
 use std::time::Instant;
 
 fn main() {
     let s1 = Instant::now();
 
-    let m = BufReader::new(File::open("./input").unwrap())
-        .split(b',')
-        .map(|ops| {
-            str::from_utf8(&ops.unwrap())
-                .unwrap()
-                .trim()
-                .parse::<i64>()
-                .unwrap()
-        })
-        .collect::<Vec<i64>>();
+    let m = intcode::Program::from_input().unwrap().into_memory();
 
-    let (_, part1_output) = intcode::compute(m.clone(), vec![1]);
+    let (_, part1_output) = intcode::compute(m.clone(), vec![1]).unwrap();
     println!(
         "part1: {:?}, ({}µs)",
         part1_output,
@@ -29,7 +17,7 @@ fn main() {
 
     let s2 = Instant::now();
 
-    let (_, part2_output) = intcode::compute(m, vec![5]);
+    let (_, part2_output) = intcode::compute(m, vec![5]).unwrap();
     println!(
         "part2: {:?}, ({}µs)",
         part2_output,
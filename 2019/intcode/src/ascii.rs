@@ -0,0 +1,109 @@
+use crate::{Intcode, IntcodeError, State};
+
+// What a text based program printed until it stopped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Output {
+    // The last line is kept even if it has no newline yet, e.g. a prompt.
+    pub lines: Vec<String>,
+    // Values that aren't ASCII, usually the answer the program prints at the end.
+    pub values: Vec<i64>,
+}
+
+impl Output {
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn value(&self) -> Option<i64> {
+        self.values.last().copied()
+    }
+}
+
+// Talks to programs that read and print ASCII text, like the scaffold robot of day 17.
+pub struct Ascii {
+    machine: Intcode,
+}
+
+impl Ascii {
+    pub fn new(machine: Intcode) -> Self {
+        Ascii { machine }
+    }
+
+    pub fn machine(&self) -> &Intcode {
+        &self.machine
+    }
+
+    pub fn machine_mut(&mut self) -> &mut Intcode {
+        &mut self.machine
+    }
+
+    pub fn into_machine(self) -> Intcode {
+        self.machine
+    }
+
+    pub fn send_line(&mut self, line: &str) {
+        self.machine
+            .extend_input(line.bytes().chain(Some(b'\n')).map(i64::from));
+    }
+
+    // Runs until the program waits for input or halts and returns what it printed.
+    pub fn run(&mut self) -> Result<(Output, State), IntcodeError> {
+        let mut output = Output::default();
+        let mut line = String::new();
+        let state = loop {
+            match self.machine.run()? {
+                State::Output(10) => output.lines.push(std::mem::take(&mut line)),
+                State::Output(value @ 0..=127) => line.push(value as u8 as char),
+                State::Output(value) => output.values.push(value),
+                state => break state,
+            }
+        };
+        if !line.is_empty() {
+            output.lines.push(line);
+        }
+        Ok((output, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::assemble;
+
+    #[test]
+    fn ascii_tests() {
+        // Asks for a name and greets it, then prints the length of the name
+        let memory = assemble(
+            "
+                    out 72
+                    out 105
+                    out 63
+            read:   in [c]
+                    eq [c], 10, [t]
+                    jt [t], done
+                    add [n], 1, [n]
+                    jt 1, read
+            done:   out 10
+                    mul [n], 1000, [n]
+                    out [n]
+                    hlt
+            c:      data 0
+            t:      data 0
+            n:      data 0
+            ",
+        )
+        .unwrap();
+        let mut ascii = Ascii::new(Intcode::new(memory));
+
+        let (output, state) = ascii.run().unwrap();
+        assert_eq!(state, State::NeedsInput);
+        assert_eq!(output.lines, vec!["Hi?"]);
+        assert_eq!(output.value(), None);
+
+        ascii.send_line("intcode");
+        let (output, state) = ascii.run().unwrap();
+        assert_eq!(state, State::Halted);
+        assert_eq!(output.text(), "");
+        assert_eq!(output.value(), Some(7000));
+    }
+}
@@ -0,0 +1,420 @@
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+
+use crate::intcode_parser;
+
+// (opcode, mnemonic, number of parameters, whether the last parameter is written to)
+const INSTRUCTIONS: [(i64, &str, usize, bool); 10] = [
+    (1, "add", 3, true),
+    (2, "mul", 3, true),
+    (3, "in", 1, true),
+    (4, "out", 1, false),
+    (5, "jt", 2, false),
+    (6, "jf", 2, false),
+    (7, "lt", 3, true),
+    (8, "eq", 3, true),
+    (9, "arb", 1, false),
+    (99, "hlt", 0, false),
+];
+
+fn by_opcode(opcode: i64) -> Option<(i64, &'static str, usize, bool)> {
+    INSTRUCTIONS.iter().find(|i| i.0 == opcode).copied()
+}
+
+pub(crate) fn by_mnemonic(mnemonic: &str) -> Option<(i64, &'static str, usize, bool)> {
+    INSTRUCTIONS.iter().find(|i| i.1 == mnemonic).copied()
+}
+
+pub fn opcode(mnemonic: &str) -> Option<i64> {
+    by_mnemonic(mnemonic).map(|i| i.0)
+}
+
+pub fn mnemonic(opcode: i64) -> Option<&'static str> {
+    by_opcode(opcode).map(|i| i.1)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parameter {
+    Position(i64),
+    Immediate(i64),
+    Relative(i64),
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Parameter::Position(address) => write!(f, "[{}]", address),
+            Parameter::Immediate(value) => write!(f, "{}", value),
+            Parameter::Relative(0) => write!(f, "[rb]"),
+            Parameter::Relative(offset) if *offset < 0 => write!(f, "[rb{}]", offset),
+            Parameter::Relative(offset) => write!(f, "[rb+{}]", offset),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    Instruction {
+        address: usize,
+        mnemonic: &'static str,
+        parameters: Vec<Parameter>,
+    },
+    Data {
+        address: usize,
+        value: i64,
+    },
+}
+
+impl Line {
+    // Number of memory cells the line takes up.
+    pub fn size(&self) -> usize {
+        match self {
+            Line::Instruction { parameters, .. } => 1 + parameters.len(),
+            Line::Data { .. } => 1,
+        }
+    }
+}
+
+// Lines are printed in the syntax the assembler reads.
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Line::Instruction {
+                mnemonic,
+                parameters,
+                ..
+            } => {
+                write!(f, "{}", mnemonic)?;
+                for (i, parameter) in parameters.iter().enumerate() {
+                    write!(f, "{}{}", if i == 0 { " " } else { ", " }, parameter)?;
+                }
+                Ok(())
+            }
+            Line::Data { value, .. } => write!(f, "data {}", value),
+        }
+    }
+}
+
+// Decodes the instruction at the address, None if it isn't a valid one.
+pub fn decode(memory: &[i64], address: usize) -> Option<Line> {
+    decode_with(|address| memory.get(address).copied(), address)
+}
+
+// Same as decode, for memory that isn't a slice like the paged memory of a running machine.
+pub fn decode_with(read: impl Fn(usize) -> Option<i64>, address: usize) -> Option<Line> {
+    let (opcode, pm1, pm2, pm3) = intcode_parser(read(address)?).ok()?;
+    let (_, mnemonic, parameter_count, writes) = by_opcode(opcode)?;
+    let parameters = (0..parameter_count)
+        .map(|i| {
+            let value = read(address + 1 + i)?;
+            match [pm1, pm2, pm3][i] {
+                0 => Some(Parameter::Position(value)),
+                1 if writes && i == parameter_count - 1 => None,
+                1 => Some(Parameter::Immediate(value)),
+                _ => Some(Parameter::Relative(value)),
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(Line::Instruction {
+        address,
+        mnemonic,
+        parameters,
+    })
+}
+
+// Addresses the instruction can continue at, as far as they are known without running it.
+fn successors(address: usize, mnemonic: &str, parameters: &[Parameter]) -> Vec<usize> {
+    let next = address + 1 + parameters.len();
+    let target = match parameters.get(1) {
+        Some(Parameter::Immediate(target)) if *target >= 0 => Some(*target as usize),
+        _ => None,
+    };
+    let condition = match parameters.first() {
+        Some(Parameter::Immediate(value)) => Some(*value != 0),
+        _ => None,
+    };
+    match (mnemonic, condition) {
+        ("hlt", _) => vec![],
+        ("jt", Some(true)) | ("jf", Some(false)) => target.into_iter().collect(),
+        ("jt", Some(false)) | ("jf", Some(true)) => vec![next],
+        ("jt", None) | ("jf", None) => target.into_iter().chain(Some(next)).collect(),
+        _ => vec![next],
+    }
+}
+
+// Decodes everything that is reachable from address 0 as code and the rest as data.
+// Jumps to computed addresses (e.g. returns from subroutines) can't be followed, code that
+// is only reached that way shows up as data.
+pub fn disassemble(memory: &[i64]) -> Vec<Line> {
+    let mut code = BTreeSet::new();
+    let mut todo = vec![0];
+    while let Some(address) = todo.pop() {
+        if code.contains(&address) {
+            continue;
+        }
+        if let Some(Line::Instruction {
+            mnemonic,
+            parameters,
+            ..
+        }) = decode(memory, address)
+        {
+            code.insert(address);
+            todo.extend(successors(address, mnemonic, &parameters));
+        }
+    }
+
+    let mut lines = vec![];
+    let mut address = 0;
+    while address < memory.len() {
+        let line = match decode(memory, address) {
+            Some(line) if code.contains(&address) => line,
+            _ => Line::Data {
+                address,
+                value: memory[address],
+            },
+        };
+        address += line.size();
+        lines.push(line);
+    }
+    lines
+}
+
+// One line per instruction or data cell, prefixed with its address.
+pub fn listing(memory: &[i64]) -> String {
+    disassemble(memory)
+        .iter()
+        .map(|line| {
+            let address = match line {
+                Line::Instruction { address, .. } | Line::Data { address, .. } => address,
+            };
+            format!("{:>5}  {}\n", address, line)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssembleError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AssembleError {}
+
+enum Value<'a> {
+    Number(i64),
+    Label(&'a str),
+}
+
+fn parse_value(s: &str) -> Result<Value<'_>, String> {
+    if let Ok(number) = s.parse() {
+        Ok(Value::Number(number))
+    } else if !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        Ok(Value::Label(s))
+    } else {
+        Err(format!("invalid value '{}'", s))
+    }
+}
+
+// Mode and value of an operand: 5, label, [5], [label], [rb], [rb+5], [rb-5]
+fn parse_operand(s: &str) -> Result<(i64, Value<'_>), String> {
+    match s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        None => Ok((1, parse_value(s)?)),
+        Some("rb") => Ok((2, Value::Number(0))),
+        Some(inner) => {
+            let inner = inner.trim();
+            match inner.strip_prefix("rb").map(str::trim) {
+                Some(offset) if offset.starts_with('+') => {
+                    Ok((2, parse_value(offset[1..].trim())?))
+                }
+                Some(offset) if offset.starts_with('-') => {
+                    match offset[1..].trim().parse::<i64>() {
+                        Ok(offset) => Ok((2, Value::Number(-offset))),
+                        Err(_) => Err(format!("invalid value '{}'", offset)),
+                    }
+                }
+                _ => Ok((0, parse_value(inner)?)),
+            }
+        }
+    }
+}
+
+// Assembles lines of `[label:] mnemonic operand, ...` or `[label:] data value, ...`.
+// Labels can be used wherever a number can, `#` starts a comment.
+pub fn assemble(source: &str) -> Result<Vec<i64>, AssembleError> {
+    let mut labels = HashMap::new();
+    let mut cells = vec![];
+
+    for (i, line) in source.lines().enumerate() {
+        let error = |message| AssembleError {
+            line: i + 1,
+            message,
+        };
+        let mut line = line.split('#').next().unwrap_or("").trim();
+        if let Some((label, rest)) = line.split_once(':') {
+            let label = label.trim();
+            if !matches!(parse_value(label), Ok(Value::Label(_))) {
+                return Err(error(format!("invalid label '{}'", label)));
+            }
+            if labels.insert(label, cells.len()).is_some() {
+                return Err(error(format!("duplicate label '{}'", label)));
+            }
+            line = rest.trim();
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        let (mnemonic, operands) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let operands = operands
+            .split(',')
+            .map(str::trim)
+            .filter(|o| !o.is_empty())
+            .collect::<Vec<_>>();
+
+        if mnemonic == "data" {
+            for operand in operands {
+                cells.push((i, parse_value(operand).map_err(error)?));
+            }
+            continue;
+        }
+
+        let (opcode, _, parameter_count, writes) = by_mnemonic(mnemonic)
+            .ok_or_else(|| error(format!("unknown mnemonic '{}'", mnemonic)))?;
+        if operands.len() != parameter_count {
+            return Err(error(format!(
+                "{} takes {} operands but got {}",
+                mnemonic,
+                parameter_count,
+                operands.len()
+            )));
+        }
+        let operands = operands
+            .into_iter()
+            .map(parse_operand)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(error)?;
+        if writes && operands.last().map(|o| o.0) == Some(1) {
+            return Err(error(format!("{} can't write to an immediate", mnemonic)));
+        }
+        let modes = operands
+            .iter()
+            .rev()
+            .fold(0, |modes, (mode, _)| modes * 10 + mode);
+        cells.push((i, Value::Number(modes * 100 + opcode)));
+        cells.extend(operands.into_iter().map(|(_, value)| (i, value)));
+    }
+
+    cells
+        .into_iter()
+        .map(|(i, value)| match value {
+            Value::Number(number) => Ok(number),
+            Value::Label(label) => {
+                labels
+                    .get(label)
+                    .map(|&address| address as i64)
+                    .ok_or_else(|| AssembleError {
+                        line: i + 1,
+                        message: format!("unknown label '{}'", label),
+                    })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute;
+
+    const DOUBLER: &str = "
+        # Doubles every input until it gets a 0
+        start: in [x]
+               jf [x], end
+               mul [x], 2, [rb+y]
+               out [rb + y]
+               jt 1, start
+        end:   hlt
+        x:     data 0
+        y:     data 0
+    ";
+
+    #[test]
+    fn assemble_tests() {
+        let memory = assemble(DOUBLER).unwrap();
+        assert_eq!(
+            memory,
+            vec![3, 15, 1006, 15, 14, 21002, 15, 2, 16, 204, 16, 1105, 1, 0, 99, 0, 0]
+        );
+        assert_eq!(compute(memory, vec![4, -2, 0]).unwrap().1, vec![8, -4]);
+    }
+
+    #[test]
+    fn assemble_error_tests() {
+        for (source, line, message) in [
+            ("add 1, 2", 1, "add takes 3 operands but got 2"),
+            ("\nfoo 1", 2, "unknown mnemonic 'foo'"),
+            ("in 5", 1, "in can't write to an immediate"),
+            ("out [x]", 1, "unknown label 'x'"),
+            ("a: hlt\na: hlt", 2, "duplicate label 'a'"),
+            ("out [rb*2]", 1, "invalid value 'rb*2'"),
+        ] {
+            assert_eq!(
+                assemble(source),
+                Err(AssembleError {
+                    line,
+                    message: message.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn disassemble_tests() {
+        let memory = assemble(DOUBLER).unwrap();
+        assert_eq!(
+            listing(&memory),
+            "    0  in [15]
+    2  jf [15], 14
+    5  mul [15], 2, [rb+16]
+    9  out [rb+16]
+   11  jt 1, 0
+   14  hlt
+   15  data 0
+   16  data 0
+"
+        );
+
+        // Everything after an unconditional jump is data until something jumps there
+        let memory = vec![1106, 0, 4, 1, 104, -5, 2105, 1, 0, 99];
+        assert_eq!(
+            listing(&memory),
+            "    0  jf 0, 4
+    3  data 1
+    4  out -5
+    6  jt 1, [rb]
+    9  data 99
+"
+        );
+    }
+
+    #[test]
+    fn roundtrip_tests() {
+        // day9 quine
+        let memory = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let source = listing(&memory)
+            .lines()
+            .map(|line| line[7..].to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(assemble(&source), Ok(memory));
+    }
+}
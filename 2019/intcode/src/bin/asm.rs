@@ -0,0 +1,19 @@
+// Assembles a program into comma separated intcode: cargo run --bin asm test.asm > input
+use std::env;
+use std::error::Error;
+use std::fs;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = env::args().nth(1).ok_or("Usage: asm <source>")?;
+    let memory = intcode::asm::assemble(&fs::read_to_string(path)?)?;
+
+    println!(
+        "{}",
+        memory
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    Ok(())
+}
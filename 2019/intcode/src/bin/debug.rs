@@ -0,0 +1,137 @@
+// Interactive debugger for intcode programs: cargo run --bin debug ../day15/input
+use std::collections::BTreeSet;
+use std::env;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use intcode::asm::{decode_with, opcode};
+use intcode::debug::{Debugger, Stop};
+use intcode::{Intcode, Program};
+
+const HELP: &str = "\
+s [n]          step n instructions (default 1)
+c              continue until a breakpoint, watchpoint, output, input or halt
+b <addr>       toggle breakpoint on an address
+o <op>         toggle breakpoint on an opcode or mnemonic
+w <addr>       toggle watchpoint on writes to an address
+i <v>[,<v>..]  push input, values separated by commas or spaces
+m <addr> [n]   show n memory cells (default 8)
+l [n]          list n instructions from ip (default 10)
+r              show ip and relative base
+t              show the trace
+q              quit";
+
+fn toggle<T: Ord>(set: &mut BTreeSet<T>, value: T) -> &'static str {
+    if set.remove(&value) {
+        "removed"
+    } else {
+        set.insert(value);
+        "set"
+    }
+}
+
+fn number<T: FromStr>(word: Option<&str>, default: Option<T>) -> Result<T, Box<dyn Error>>
+where
+    T::Err: Error + 'static,
+{
+    match (word, default) {
+        (Some(word), _) => Ok(word.parse()?),
+        (None, Some(default)) => Ok(default),
+        (None, None) => Err("missing argument".into()),
+    }
+}
+
+// Returns false once the debugger should quit.
+fn command(debugger: &mut Debugger, line: &str) -> Result<bool, Box<dyn Error>> {
+    let mut words = line.split_whitespace();
+    match words.next().unwrap_or("s") {
+        "s" => {
+            for _ in 0..number(words.next(), Some(1))? {
+                let stop = debugger.step()?;
+                if stop != Stop::Step {
+                    println!("{}", stop);
+                    break;
+                }
+            }
+        }
+        "c" => println!("{}", debugger.cont()?),
+        "b" => {
+            let address = number(words.next(), None)?;
+            println!("{}", toggle(&mut debugger.breakpoints, address));
+        }
+        "o" => {
+            let op = words.next().ok_or("missing argument")?;
+            let op = match opcode(op) {
+                Some(op) => op,
+                None => number(Some(op), None)?,
+            };
+            println!("{}", toggle(&mut debugger.opcode_breakpoints, op));
+        }
+        "w" => {
+            let address = number(words.next(), None)?;
+            println!("{}", toggle(&mut debugger.watchpoints, address));
+        }
+        "i" => {
+            let values = words
+                .flat_map(|word| word.split(','))
+                .filter(|v| !v.is_empty())
+                .map(|v| number(Some(v), None))
+                .collect::<Result<Vec<i64>, _>>()?;
+            if values.is_empty() {
+                return Err("missing argument".into());
+            }
+            debugger.machine_mut().extend_input(values);
+        }
+        "m" => {
+            let address: usize = number(words.next(), None)?;
+            let memory = debugger.machine().memory();
+            let count: usize = number(words.next(), Some(8))?;
+            for a in address..address.saturating_add(count) {
+                println!("{:>5}  {}", a, memory.get(a));
+            }
+        }
+        "l" => {
+            let memory = debugger.machine().memory();
+            let mut address = debugger.machine().ip();
+            for _ in 0..number(words.next(), Some(10))? {
+                match decode_with(|address| Some(memory.get(address)), address) {
+                    Some(line) => {
+                        println!("{:>5}  {}", address, line);
+                        address += line.size();
+                    }
+                    None => break,
+                }
+            }
+        }
+        "r" => println!(
+            "ip {}  rb {}",
+            debugger.machine().ip(),
+            debugger.machine().relative_base()
+        ),
+        "t" => debugger.trace().for_each(|entry| println!("{}", entry)),
+        "q" => return Ok(false),
+        _ => println!("{}", HELP),
+    }
+    Ok(true)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = env::args().nth(1).ok_or("Usage: debug <program>")?;
+    let memory = Program::from_path(path)?.into_memory();
+    let mut debugger = Debugger::new(Intcode::new(memory), 1000);
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        match command(&mut debugger, &line) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => println!("error: {}", e),
+        }
+    }
+}
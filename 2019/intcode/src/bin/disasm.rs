@@ -0,0 +1,13 @@
+// Prints the disassembly of an intcode program: cargo run --bin disasm ../day9/input
+use std::env;
+use std::error::Error;
+
+use intcode::Program;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = env::args().nth(1).ok_or("Usage: disasm <program>")?;
+    let memory = Program::from_path(path)?.into_memory();
+
+    print!("{}", intcode::asm::listing(&memory));
+    Ok(())
+}
@@ -0,0 +1,44 @@
+// Runs an intcode program with profiling and prints where it spent its time:
+// cargo run --release --bin profile ../day9/input 2 day9.folded
+// The optional folded file can be turned into a flamegraph, e.g. with inferno-flamegraph.
+use std::env;
+use std::error::Error;
+use std::fs;
+
+use intcode::{Program, State};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let path = args
+        .next()
+        .ok_or("Usage: profile <program> [input,...] [folded file]")?;
+    let inputs = args
+        .next()
+        .filter(|inputs| !inputs.trim().is_empty())
+        .map(|inputs| inputs.parse::<Program>())
+        .transpose()?
+        .map_or(vec![], Program::into_memory);
+
+    let mut machine = Program::from_path(path)?.machine();
+    machine.extend_input(inputs);
+    machine.enable_profiling();
+    let mut outputs = 0;
+    loop {
+        match machine.run()? {
+            State::Output(_) => outputs += 1,
+            State::NeedsInput => {
+                eprintln!("Stopped waiting for input");
+                break;
+            }
+            State::Halted => break,
+        }
+    }
+
+    let profile = machine.take_profile().unwrap_or_default();
+    println!("{} outputs\n", outputs);
+    print!("{}", profile.table(20));
+    if let Some(folded) = args.next() {
+        fs::write(folded, profile.folded())?;
+    }
+    Ok(())
+}
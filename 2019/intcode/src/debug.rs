@@ -0,0 +1,297 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+
+use crate::asm::{by_mnemonic, decode_with, Line, Parameter};
+use crate::{Intcode, IntcodeError, State};
+
+// Why the debugger gave control back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    // A single step executed without anything of note happening.
+    Step,
+    State(State),
+    Breakpoint(usize),
+    Opcode(i64),
+    Watchpoint { address: usize, old: i64, new: i64 },
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stop::Step => write!(f, "step"),
+            Stop::State(State::NeedsInput) => write!(f, "needs input"),
+            Stop::State(State::Output(value)) => write!(f, "output {}", value),
+            Stop::State(State::Halted) => write!(f, "halted"),
+            Stop::Breakpoint(address) => write!(f, "breakpoint at {}", address),
+            Stop::Opcode(opcode) => write!(f, "opcode breakpoint on {}", opcode),
+            Stop::Watchpoint { address, old, new } => {
+                write!(f, "watchpoint at {}: {} -> {}", address, old, new)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub ip: usize,
+    pub relative_base: i64,
+    pub line: Line,
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>5}  rb {:<5}  {}",
+            self.ip, self.relative_base, self.line
+        )
+    }
+}
+
+// Wraps an Intcode machine with breakpoints on addresses and opcodes, watchpoints on memory
+// writes and a trace of the last executed instructions.
+pub struct Debugger {
+    machine: Intcode,
+    pub breakpoints: BTreeSet<usize>,
+    pub opcode_breakpoints: BTreeSet<i64>,
+    pub watchpoints: BTreeSet<usize>,
+    trace: VecDeque<TraceEntry>,
+    trace_capacity: usize,
+    // Set when cont stopped at a breakpoint, so the next cont doesn't stop there again.
+    at_breakpoint: bool,
+}
+
+impl Debugger {
+    pub fn new(machine: Intcode, trace_capacity: usize) -> Self {
+        Debugger {
+            machine,
+            breakpoints: BTreeSet::new(),
+            opcode_breakpoints: BTreeSet::new(),
+            watchpoints: BTreeSet::new(),
+            trace: VecDeque::with_capacity(trace_capacity),
+            trace_capacity,
+            at_breakpoint: false,
+        }
+    }
+
+    pub fn machine(&self) -> &Intcode {
+        &self.machine
+    }
+
+    pub fn machine_mut(&mut self) -> &mut Intcode {
+        &mut self.machine
+    }
+
+    pub fn into_machine(self) -> Intcode {
+        self.machine
+    }
+
+    // Oldest entry first.
+    pub fn trace(&self) -> impl Iterator<Item = &TraceEntry> {
+        self.trace.iter()
+    }
+
+    fn value(&self, address: usize) -> i64 {
+        self.machine.memory().get(address)
+    }
+
+    fn line_at_ip(&self) -> Line {
+        let ip = self.machine.ip();
+        let memory = self.machine.memory();
+        decode_with(|address| Some(memory.get(address)), ip).unwrap_or(Line::Data {
+            address: ip,
+            value: self.value(ip),
+        })
+    }
+
+    // The address an instruction is going to write to, if any.
+    fn write_address(&self, line: &Line) -> Option<usize> {
+        match line {
+            Line::Instruction {
+                mnemonic,
+                parameters,
+                ..
+            } if by_mnemonic(mnemonic)?.3 => match parameters.last()? {
+                Parameter::Position(address) => Some(*address),
+                // An overflowing address is left to the machine to report
+                Parameter::Relative(offset) => self.machine.relative_base().checked_add(*offset),
+                Parameter::Immediate(_) => None,
+            }
+            .filter(|&address| address >= 0)
+            .map(|address| address as usize),
+            _ => None,
+        }
+    }
+
+    // Executes one instruction.
+    pub fn step(&mut self) -> Result<Stop, IntcodeError> {
+        let line = self.line_at_ip();
+        let watched = self
+            .write_address(&line)
+            .filter(|address| self.watchpoints.contains(address))
+            .map(|address| (address, self.value(address)));
+        let entry = TraceEntry {
+            ip: self.machine.ip(),
+            relative_base: self.machine.relative_base(),
+            line,
+        };
+
+        let state = self.machine.step()?;
+        self.at_breakpoint = false;
+
+        if state != Some(State::NeedsInput) && self.trace_capacity > 0 {
+            if self.trace.len() == self.trace_capacity {
+                self.trace.pop_front();
+            }
+            self.trace.push_back(entry);
+        }
+        Ok(match (state, watched) {
+            (Some(state), _) => Stop::State(state),
+            (None, Some((address, old))) => Stop::Watchpoint {
+                address,
+                old,
+                new: self.value(address),
+            },
+            (None, None) => Stop::Step,
+        })
+    }
+
+    // Runs until a breakpoint or watchpoint is hit or the machine stops by itself.
+    // Breakpoints stop before the instruction is executed.
+    pub fn cont(&mut self) -> Result<Stop, IntcodeError> {
+        loop {
+            if !self.at_breakpoint {
+                let ip = self.machine.ip();
+                let opcode = self.value(ip) % 100;
+                let stop = if self.breakpoints.contains(&ip) {
+                    Some(Stop::Breakpoint(ip))
+                } else if self.opcode_breakpoints.contains(&opcode) {
+                    Some(Stop::Opcode(opcode))
+                } else {
+                    None
+                };
+                if let Some(stop) = stop {
+                    self.at_breakpoint = true;
+                    return Ok(stop);
+                }
+            }
+            let stop = self.step()?;
+            if stop != Stop::Step {
+                return Ok(stop);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::assemble;
+
+    const COUNTER: &str = "
+        loop: add [i], 1, [i]
+              out [i]
+              lt [i], 3, [rb+c]
+              jt [c], loop
+              hlt
+        i:    data 0
+        c:    data 0
+    ";
+
+    fn debugger(trace_capacity: usize) -> Debugger {
+        Debugger::new(Intcode::new(assemble(COUNTER).unwrap()), trace_capacity)
+    }
+
+    #[test]
+    fn step_tests() {
+        let mut debugger = debugger(0);
+        assert_eq!(debugger.step(), Ok(Stop::Step));
+        assert_eq!(debugger.step(), Ok(Stop::State(State::Output(1))));
+        assert_eq!(debugger.machine().ip(), 6);
+        assert_eq!(debugger.trace().count(), 0);
+    }
+
+    #[test]
+    fn breakpoint_tests() {
+        let mut debugger = debugger(0);
+        debugger.breakpoints.insert(4);
+        debugger.opcode_breakpoints.insert(99);
+
+        assert_eq!(debugger.cont(), Ok(Stop::Breakpoint(4)));
+        assert_eq!(debugger.cont(), Ok(Stop::State(State::Output(1))));
+        assert_eq!(debugger.cont(), Ok(Stop::Breakpoint(4)));
+
+        debugger.breakpoints.clear();
+        assert_eq!(debugger.cont(), Ok(Stop::State(State::Output(2))));
+
+        // The output stopped right in front of it
+        debugger.breakpoints.insert(6);
+        assert_eq!(debugger.cont(), Ok(Stop::Breakpoint(6)));
+        debugger.breakpoints.clear();
+        assert_eq!(debugger.cont(), Ok(Stop::State(State::Output(3))));
+        assert_eq!(debugger.cont(), Ok(Stop::Opcode(99)));
+        assert_eq!(debugger.cont(), Ok(Stop::State(State::Halted)));
+    }
+
+    #[test]
+    fn watchpoint_tests() {
+        let mut debugger = debugger(0);
+        debugger.watchpoints.insert(15);
+
+        // Written by a relative mode parameter
+        for (old, new) in [(0, 1), (1, 1), (1, 0)] {
+            loop {
+                match debugger.cont().unwrap() {
+                    Stop::State(State::Output(_)) => {}
+                    stop => {
+                        assert_eq!(
+                            stop,
+                            Stop::Watchpoint {
+                                address: 15,
+                                old,
+                                new
+                            }
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn trace_tests() {
+        let mut debugger = debugger(3);
+        while debugger.cont() != Ok(Stop::State(State::Halted)) {}
+
+        let trace = debugger.trace().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            trace,
+            vec![
+                "    6  rb 0      lt [14], 3, [rb+15]",
+                "   10  rb 0      jt [15], 0",
+                "   13  rb 0      hlt",
+            ]
+        );
+    }
+
+    #[test]
+    fn error_tests() {
+        let mut debugger = Debugger::new(Intcode::new(vec![1, 0, 0, 0, 42]), 10);
+        assert_eq!(debugger.cont().map_err(|e| e.ip), Err(4));
+        assert_eq!(debugger.trace().count(), 1);
+        assert_eq!(
+            debugger.step().map_err(|e| e.to_string()),
+            Err("invalid opcode at ip 4 (opcode 42)".to_string())
+        );
+
+        // Writes to relative base + offset, which doesn't fit into an i64
+        let mut debugger = Debugger::new(Intcode::new(vec![109, i64::MAX, 21101, 1, 1, 1, 99]), 10);
+        debugger.watchpoints.insert(0);
+        assert!(debugger.step().is_ok());
+        assert_eq!(
+            debugger.step().map_err(|e| e.to_string()),
+            Err("overflow at ip 2 (opcode 21101)".to_string())
+        );
+    }
+}
@@ -0,0 +1,360 @@
+// The interpreter as it was before paged memory and the instruction cache, with one flat
+// vector for memory and opcodes decoded on every step. Kept to check the current one against.
+use std::collections::VecDeque;
+
+use crate::{intcode_parser, IntcodeError, Reason, State};
+
+fn allocate_memory_for_index(m: &mut Vec<i64>, requested_index: i64) -> Result<usize, Reason> {
+    if requested_index < 0 {
+        return Err(Reason::NegativeAddress(requested_index));
+    }
+    let requested_index = requested_index as usize;
+    if m.len() < requested_index + 1 {
+        m.resize(requested_index + 1, 0);
+    }
+    Ok(requested_index)
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Legacy {
+    memory: Vec<i64>,
+    ip: usize,
+    relative_base: i64,
+    inputs: VecDeque<i64>,
+}
+
+impl Legacy {
+    pub(crate) fn new(memory: Vec<i64>) -> Self {
+        Legacy {
+            memory,
+            ip: 0,
+            relative_base: 0,
+            inputs: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn into_memory(self) -> Vec<i64> {
+        self.memory
+    }
+
+    pub(crate) fn extend_input(&mut self, values: impl IntoIterator<Item = i64>) {
+        self.inputs.extend(values);
+    }
+
+    fn error(&self, reason: Reason) -> IntcodeError {
+        IntcodeError {
+            ip: self.ip,
+            opcode: self.memory.get(self.ip).copied().unwrap_or(0),
+            reason,
+        }
+    }
+
+    fn read(&mut self, parameter: usize, parameter_mode: i64) -> Result<i64, Reason> {
+        let memory_cell =
+            allocate_memory_for_index(&mut self.memory, (self.ip + parameter) as i64)?;
+        let memory_cell = self.memory[memory_cell];
+        Ok(match parameter_mode {
+            0 => {
+                let memory_cell = allocate_memory_for_index(&mut self.memory, memory_cell)?;
+                self.memory[memory_cell]
+            }
+            1 => memory_cell,
+            2 => {
+                let memory_cell =
+                    allocate_memory_for_index(&mut self.memory, self.relative_base + memory_cell)?;
+                self.memory[memory_cell]
+            }
+            pm => return Err(Reason::InvalidMode(pm)),
+        })
+    }
+
+    fn write(&mut self, parameter: usize, parameter_mode: i64, value: i64) -> Result<(), Reason> {
+        let memory_cell =
+            allocate_memory_for_index(&mut self.memory, (self.ip + parameter) as i64)?;
+        let memory_cell = self.memory[memory_cell];
+        let memory_cell = match parameter_mode {
+            0 => allocate_memory_for_index(&mut self.memory, memory_cell)?,
+            1 => return Err(Reason::WriteInImmediateMode),
+            2 => allocate_memory_for_index(&mut self.memory, self.relative_base + memory_cell)?,
+            pm => return Err(Reason::InvalidMode(pm)),
+        };
+        self.memory[memory_cell] = value;
+        Ok(())
+    }
+
+    fn jump(&mut self, target: i64) -> Result<(), Reason> {
+        if target < 0 {
+            return Err(Reason::NegativeAddress(target));
+        }
+        self.ip = target as usize;
+        Ok(())
+    }
+
+    // Executes the instruction at ip. Returns a state if the machine has to stop there.
+    // On errors ip is left at the failing instruction.
+    fn execute(&mut self) -> Result<Option<State>, Reason> {
+        let ip = allocate_memory_for_index(&mut self.memory, self.ip as i64)?;
+        let (ins, pm1, pm2, pm3) = intcode_parser(self.memory[ip])?;
+        match ins {
+            99 => return Ok(Some(State::Halted)),
+            1 => {
+                // add
+                let operand_1 = self.read(1, pm1)?;
+                let operand_2 = self.read(2, pm2)?;
+                self.write(3, pm3, operand_1 + operand_2)?;
+                self.ip += 4;
+            }
+            2 => {
+                // mul
+                let operand_1 = self.read(1, pm1)?;
+                let operand_2 = self.read(2, pm2)?;
+                self.write(3, pm3, operand_1 * operand_2)?;
+                self.ip += 4;
+            }
+            3 => {
+                // input
+                match self.inputs.front() {
+                    Some(&input) => self.write(1, pm1, input)?,
+                    None => return Ok(Some(State::NeedsInput)),
+                }
+                self.inputs.pop_front();
+                self.ip += 2;
+            }
+            4 => {
+                // output
+                let output = self.read(1, pm1)?;
+                self.ip += 2;
+                return Ok(Some(State::Output(output)));
+            }
+            5 => {
+                // jump-if-true
+                if self.read(1, pm1)? != 0 {
+                    let target = self.read(2, pm2)?;
+                    self.jump(target)?;
+                } else {
+                    self.ip += 3;
+                }
+            }
+            6 => {
+                // jump-if-false
+                if self.read(1, pm1)? == 0 {
+                    let target = self.read(2, pm2)?;
+                    self.jump(target)?;
+                } else {
+                    self.ip += 3;
+                }
+            }
+            7 => {
+                // less than
+                let comparator_l = self.read(1, pm1)?;
+                let comparator_r = self.read(2, pm2)?;
+                self.write(3, pm3, (comparator_l < comparator_r) as i64)?;
+                self.ip += 4;
+            }
+            8 => {
+                // equals
+                let comparator_l = self.read(1, pm1)?;
+                let comparator_r = self.read(2, pm2)?;
+                self.write(3, pm3, (comparator_l == comparator_r) as i64)?;
+                self.ip += 4;
+            }
+            9 => {
+                // relative base offset
+                self.relative_base += self.read(1, pm1)?;
+                self.ip += 2;
+            }
+            _ => return Err(Reason::InvalidOpcode),
+        }
+        Ok(None)
+    }
+
+    // Executes a single instruction, an input instruction without input stays at ip.
+    pub(crate) fn step(&mut self) -> Result<Option<State>, IntcodeError> {
+        self.execute().map_err(|reason| self.error(reason))
+    }
+
+    // Executes instructions until the program halts, outputs a value or wants an input that
+    // hasn't been pushed yet. Running a halted machine again returns Halted right away.
+    pub(crate) fn run(&mut self) -> Result<State, IntcodeError> {
+        loop {
+            if let Some(state) = self.step()? {
+                return Ok(state);
+            }
+        }
+    }
+
+    // Runs until the program halts and returns everything it printed.
+    pub(crate) fn run_to_halt(&mut self) -> Result<Vec<i64>, IntcodeError> {
+        let mut outputs = vec![];
+        loop {
+            match self.run()? {
+                State::Output(output) => outputs.push(output),
+                State::Halted => return Ok(outputs),
+                State::NeedsInput => return Err(self.error(Reason::InputExhausted)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::assemble;
+    use crate::Intcode;
+    use aoc_util::compare::{report, Alternatives};
+
+    type Input = (Vec<i64>, Vec<i64>);
+    // Outputs and memory without trailing zeros, the old interpreter also grew memory on reads
+    type Output = Result<(Vec<i64>, Vec<i64>), IntcodeError>;
+
+    fn trimmed(mut memory: Vec<i64>) -> Vec<i64> {
+        while memory.last() == Some(&0) {
+            memory.pop();
+        }
+        memory
+    }
+
+    fn alternatives<'a>() -> Alternatives<'a, Input, Output> {
+        Alternatives::new()
+            .add("legacy", |(memory, inputs): &Input| {
+                let mut machine = Legacy::new(memory.clone());
+                machine.extend_input(inputs.iter().copied());
+                let outputs = machine.run_to_halt()?;
+                Ok((outputs, trimmed(machine.into_memory())))
+            })
+            .add("paged", |(memory, inputs): &Input| {
+                let mut machine = Intcode::new(memory.clone());
+                machine.extend_input(inputs.iter().copied());
+                let outputs = machine.run_to_halt()?;
+                Ok((outputs, trimmed(machine.into_memory())))
+            })
+    }
+
+    // Sums up 1..=n with a loop, most of the time is spent decoding and running instructions
+    fn sum_loop(n: i64) -> Input {
+        let memory = assemble(
+            "
+                    in [n]
+            loop:   add [sum], [n], [sum]
+                    add [n], -1, [n]
+                    jt [n], loop
+                    out [sum]
+                    hlt
+            n:      data 0
+            sum:    data 0
+            ",
+        )
+        .unwrap();
+        (memory, vec![n])
+    }
+
+    const QUINE: [i64; 16] = [
+        109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+    ];
+    const COMPARE_TO_8: [i64; 47] = [
+        3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0, 0,
+        1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4, 20,
+        1105, 1, 46, 98, 99,
+    ];
+
+    fn inputs() -> Vec<Input> {
+        let mut inputs = vec![(QUINE.to_vec(), vec![]), sum_loop(1000)];
+        inputs.extend((6..=10).map(|i| (COMPARE_TO_8.to_vec(), vec![i])));
+        inputs.push((vec![3, 0, 3, 0, 99], vec![1]));
+        inputs.push((vec![109, -5, 204, 2], vec![]));
+        inputs
+    }
+
+    // Day 2: (program, result) to the first (noun, verb) that makes the program leave the result
+    // at address 0, up to 10000 short runs
+    type Day2 = (Vec<i64>, i64);
+
+    fn day2_alternatives<'a>() -> Alternatives<'a, Day2, Option<(i64, i64)>> {
+        fn search(
+            program: &[i64],
+            result: i64,
+            run: impl Fn(Vec<i64>) -> Option<i64>,
+        ) -> Option<(i64, i64)> {
+            (0..100)
+                .flat_map(|noun| (0..100).map(move |verb| (noun, verb)))
+                .find(|&(noun, verb)| {
+                    let mut memory = program.to_vec();
+                    memory[1] = noun;
+                    memory[2] = verb;
+                    run(memory) == Some(result)
+                })
+        }
+
+        Alternatives::new()
+            .add("legacy", |(program, result): &Day2| {
+                search(program, *result, |memory| {
+                    let mut machine = Legacy::new(memory);
+                    machine.run_to_halt().ok()?;
+                    Some(machine.into_memory()[0])
+                })
+            })
+            .add("paged", |(program, result): &Day2| {
+                search(program, *result, |memory| {
+                    let mut machine = Intcode::new(memory);
+                    machine.run_to_halt().ok()?;
+                    Some(machine.memory().get(0))
+                })
+            })
+    }
+
+    // Shaped like a day 2 input: 1,noun,verb,3, a chain of adds and muls of constants into 3
+    // and the result in 0
+    fn day2(noun: i64, verb: i64) -> Day2 {
+        let steps = 30;
+        let constants = 4 * (steps + 2) + 1;
+        let mut program = vec![1, 0, 0, 3];
+        for step in 0..steps {
+            let opcode = if step % 3 == 0 { 2 } else { 1 };
+            program.extend([opcode, 3, constants + step % 5, 3]);
+        }
+        program.extend([1, 3, constants, 0, 99, 2, 3, 5, 7, 11]);
+
+        let mut memory = program.clone();
+        memory[1] = noun;
+        memory[2] = verb;
+        let mut machine = Intcode::new(memory);
+        machine.run_to_halt().unwrap();
+        (program, machine.memory().get(0))
+    }
+
+    #[test]
+    fn test_alternatives_agree() {
+        alternatives().check_all(&inputs()).unwrap();
+        let (program, result) = day2(12, 2);
+        let (noun, verb) = day2_alternatives()
+            .check(&(program.clone(), result))
+            .unwrap()
+            .unwrap();
+        assert_eq!(day2(noun, verb).1, result);
+        // Every pair runs
+        assert_eq!(day2_alternatives().check(&(program, -1)), Ok(None));
+    }
+
+    // cargo test --release bench -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_alternatives() {
+        let alternatives = alternatives();
+        println!(
+            "long loop\n{}",
+            report(&alternatives.bench(&sum_loop(100_000), 20))
+        );
+        println!(
+            "short loop\n{}",
+            report(&alternatives.bench(&sum_loop(10), 1000))
+        );
+        let quine = (QUINE.to_vec(), vec![]);
+        println!("quine\n{}", report(&alternatives.bench(&quine, 1000)));
+        let compare = (COMPARE_TO_8.to_vec(), vec![9]);
+        println!("day 5\n{}", report(&alternatives.bench(&compare, 1000)));
+        println!(
+            "day 2, 10000 runs\n{}",
+            report(&day2_alternatives().bench(&(day2(0, 0).0, -1), 10))
+        );
+    }
+}
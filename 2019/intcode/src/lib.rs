@@ -1,8 +1,27 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
 use std::thread;
 use std::thread::JoinHandle;
 
+use memory::Memory;
+use profile::Profile;
+
+pub mod ascii;
+pub mod asm;
+pub mod debug;
+#[cfg(test)]
+mod legacy;
+pub mod memory;
+pub mod network;
+pub mod profile;
+pub mod program;
+pub mod snapshot;
+
+pub use program::{Program, ProgramError};
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,7 +39,7 @@ mod tests {
         for (mut memory, noun, verb, expected_result) in day2_part1_testdata() {
             memory[1] = noun;
             memory[2] = verb;
-            let (m, _) = compute(memory, vec![]);
+            let (m, _) = compute(memory, vec![]).unwrap();
             assert_eq!(m[0], expected_result);
         }
     }
@@ -52,7 +71,7 @@ mod tests {
     #[test]
     fn day5_part2_tests() {
         for (memory, input, expected_output) in day5_part2_testdata() {
-            let (_, output) = compute(memory, vec![input]);
+            let (_, output) = compute(memory, vec![input]).unwrap();
             assert_eq!(output[0], expected_output);
         }
     }
@@ -86,11 +105,157 @@ mod tests {
             ),
             (vec![104, 1125899906842624, 99], vec![1125899906842624]),
         ] {
-            let (_, output) = compute(memory, vec![]);
+            let (_, output) = compute(memory, vec![]).unwrap();
             assert_eq!(output, expected_output);
         }
     }
 
+    #[test]
+    fn day5_part2_tests_step_wise() {
+        for (memory, input, expected_output) in day5_part2_testdata() {
+            let mut machine = Intcode::new(memory);
+            assert_eq!(machine.run(), Ok(State::NeedsInput));
+            assert_eq!(machine.run(), Ok(State::NeedsInput));
+            machine.push_input(input);
+            assert_eq!(machine.run(), Ok(State::Output(expected_output)));
+            assert_eq!(machine.run(), Ok(State::Halted));
+            assert_eq!(machine.run(), Ok(State::Halted));
+        }
+    }
+
+    #[test]
+    fn interactive_tests() {
+        // Doubles every input until it gets a 0
+        let memory = vec![
+            3, 15, 1006, 15, 14, 1002, 15, 2, 16, 4, 16, 1105, 1, 0, 99, 0, 0,
+        ];
+        let mut machine = Intcode::new(memory);
+        for input in [3, -7, 21] {
+            assert_eq!(machine.run(), Ok(State::NeedsInput));
+            machine.push_input(input);
+            assert_eq!(machine.run(), Ok(State::Output(input * 2)));
+        }
+
+        let mut copy = machine.clone();
+        copy.extend_input(vec![5, 0]);
+        assert_eq!(copy.run_to_halt(), Ok(vec![10]));
+
+        machine.push_input(0);
+        assert_eq!(machine.run(), Ok(State::Halted));
+        assert_eq!(machine.ip(), 14);
+    }
+
+    #[test]
+    fn error_tests() {
+        for (memory, inputs, expected_error) in [
+            (vec![98, 0, 0, 0], vec![], (0, 98, Reason::InvalidOpcode)),
+            (vec![-1], vec![], (0, -1, Reason::InvalidOpcode)),
+            (
+                vec![1, 0, 0, 0, 304, 0],
+                vec![],
+                (4, 304, Reason::InvalidMode(3)),
+            ),
+            (
+                vec![123456, 99],
+                vec![],
+                (0, 123456, Reason::InvalidMode(4)),
+            ),
+            (vec![4, -3, 99], vec![], (0, 4, Reason::NegativeAddress(-3))),
+            (
+                vec![1105, 1, -8],
+                vec![],
+                (0, 1105, Reason::NegativeAddress(-8)),
+            ),
+            (
+                vec![109, -5, 204, 2],
+                vec![],
+                (2, 204, Reason::NegativeAddress(-3)),
+            ),
+            (
+                vec![11101, 1, 1, 0, 99],
+                vec![],
+                (0, 11101, Reason::WriteInImmediateMode),
+            ),
+            (
+                vec![3, 0, 3, 0, 99],
+                vec![1],
+                (2, 3, Reason::InputExhausted),
+            ),
+            (
+                vec![1101, i64::MAX, 1, 0, 99],
+                vec![],
+                (0, 1101, Reason::Overflow),
+            ),
+            (
+                vec![1102, i64::MAX, 2, 0, 99],
+                vec![],
+                (0, 1102, Reason::Overflow),
+            ),
+            (
+                vec![109, i64::MAX, 109, 1, 99],
+                vec![],
+                (2, 109, Reason::Overflow),
+            ),
+            (
+                vec![109, i64::MAX, 204, 1, 99],
+                vec![],
+                (2, 204, Reason::Overflow),
+            ),
+        ] {
+            let (ip, opcode, reason) = expected_error;
+            let error = IntcodeError { ip, opcode, reason };
+            assert_eq!(compute(memory, inputs), Err(error));
+        }
+
+        let mut machine = Intcode::new(vec![3, 2, 0, 0, 0, 99]);
+        machine.push_input(11101);
+        assert_eq!(
+            machine.run().map_err(|e| e.to_string()),
+            Err("write in immediate mode at ip 2 (opcode 11101)".to_string())
+        );
+    }
+
+    #[test]
+    fn huge_address_tests() {
+        let address = 1 << 40;
+        let mut machine = Intcode::new(vec![1101, 7, 0, address, 4, address, 4, address + 1, 99]);
+        assert_eq!(machine.run_to_halt(), Ok(vec![7, 0]));
+        assert_eq!(machine.memory().pages().len(), 2);
+
+        let mut machine = Intcode::new(vec![21101, 7, 0, 0, 204, 0, 99]);
+        machine.relative_base = address;
+        assert_eq!(machine.run(), Ok(State::Output(7)));
+        assert_eq!(machine.memory().get(address as usize), 7);
+        assert_eq!(machine.memory().len(), address as usize + 1);
+    }
+
+    #[test]
+    fn self_modifying_tests() {
+        // Adds 2 to x a thousand times, but long after the instruction at op was cached, it is
+        // changed to add 3 for the last three rounds and to multiply by 3 for the very last one
+        let memory = asm::assemble(
+            "
+                    arb op
+            op:     add [x], 2, [x]
+                    add [count], -1, [count]
+                    eq [count], 3, [t]
+                    jf [t], opcode
+                    add 3, 0, [rb+2]
+            opcode: eq [count], 1, [t]
+                    jf [t], next
+                    add 1002, 0, [op]
+            next:   jt [count], op
+                    out [x]
+                    hlt
+            x:      data 0
+            count:  data 1000
+            t:      data 0
+            ",
+        )
+        .unwrap();
+        assert_eq!(compute(memory, vec![]).unwrap().1, vec![(1994 + 6) * 3]);
+    }
+
     #[test]
     fn intcode_parser_tests() {
         for (intcode, expected_result) in vec![
@@ -101,14 +266,15 @@ mod tests {
             (10104, (4, 1, 0, 1)),
             (11105, (5, 1, 1, 1)),
         ] {
-            assert_eq!(intcode_parser(intcode), expected_result);
+            assert_eq!(intcode_parser(intcode), Ok(expected_result));
         }
     }
 }
 
-fn intcode_parser(intcode: i64) -> (i64, i64, i64, i64) {
-    assert!(intcode >= 0);
-    assert!(intcode < 100000);
+fn intcode_parser(intcode: i64) -> Result<(i64, i64, i64, i64), Reason> {
+    if intcode < 0 {
+        return Err(Reason::InvalidOpcode);
+    }
     let mut instruction = intcode;
 
     let parameter_mode_3 = instruction / 10000;
@@ -118,175 +284,462 @@ fn intcode_parser(intcode: i64) -> (i64, i64, i64, i64) {
     let parameter_mode_1 = instruction / 100;
     instruction -= parameter_mode_1 * 100;
 
-    (
+    if let Some(&mode) = [parameter_mode_1, parameter_mode_2, parameter_mode_3]
+        .iter()
+        .find(|&&mode| mode > 2)
+    {
+        return Err(Reason::InvalidMode(mode));
+    }
+
+    Ok((
         instruction,
         parameter_mode_1,
         parameter_mode_2,
         parameter_mode_3,
-    )
+    ))
+}
+
+fn checked(value: Option<i64>) -> Result<i64, Reason> {
+    value.ok_or(Reason::Overflow)
 }
 
-fn allocate_memory_for_index(m: &mut Vec<i64>, requested_index: usize) -> usize {
-    if m.len() < requested_index + 1 {
-        m.resize(requested_index + 1, 0);
+fn address(address: i64) -> Result<usize, Reason> {
+    if address < 0 {
+        return Err(Reason::NegativeAddress(address));
     }
-    requested_index
+    Ok(address as usize)
 }
 
-fn read_value(
-    m: &mut Vec<i64>,
-    memory_cell: usize,
-    parameter_mode: i64,
-    relative_base: i64,
-) -> i64 {
-    allocate_memory_for_index(m, memory_cell);
-    let memory_cell = m[memory_cell];
-    match parameter_mode {
-        0 => {
-            let memory_cell = allocate_memory_for_index(m, memory_cell as usize);
-            m[memory_cell]
-        }
-        1 => memory_cell as i64,
-        2 => {
-            let memory_cell = allocate_memory_for_index(m, (relative_base + memory_cell) as usize);
-            m[memory_cell]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    InvalidOpcode,
+    InvalidMode(i64),
+    NegativeAddress(i64),
+    WriteInImmediateMode,
+    // The program wanted an input but none was given and none can follow.
+    InputExhausted,
+    // An addition, multiplication or relative address doesn't fit into an i64.
+    Overflow,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::InvalidOpcode => write!(f, "invalid opcode"),
+            Reason::InvalidMode(mode) => write!(f, "invalid parameter mode {}", mode),
+            Reason::NegativeAddress(address) => write!(f, "negative address {}", address),
+            Reason::WriteInImmediateMode => write!(f, "write in immediate mode"),
+            Reason::InputExhausted => write!(f, "input exhausted"),
+            Reason::Overflow => write!(f, "overflow"),
         }
-        pm => panic!(format!("unknown read parameter mode '{}'", pm)),
     }
 }
 
-fn write_value(
-    m: &mut Vec<i64>,
-    memory_cell: usize,
-    parameter_mode: i64,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntcodeError {
+    pub ip: usize,
+    pub opcode: i64,
+    pub reason: Reason,
+}
+
+impl fmt::Display for IntcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at ip {} (opcode {})",
+            self.reason, self.ip, self.opcode
+        )
+    }
+}
+
+impl Error for IntcodeError {}
+
+// Why Intcode::run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    // Waiting at an input instruction, continues after push_input.
+    NeedsInput,
+    Output(i64),
+    Halted,
+}
+
+// Intcode machine that keeps its memory, instruction pointer and relative base between runs,
+// so interactive programs can be driven from a plain loop on one thread.
+#[derive(Debug)]
+pub struct Intcode {
+    memory: Memory,
+    ip: usize,
     relative_base: i64,
-    value: i64,
-) {
-    allocate_memory_for_index(m, memory_cell);
-    let memory_cell = m[memory_cell];
-    match parameter_mode {
-        0 => {
-            let memory_cell = allocate_memory_for_index(m, memory_cell as usize);
-            m[memory_cell] = value
-        }
-        2 => {
-            let memory_cell = allocate_memory_for_index(m, (relative_base + memory_cell) as usize);
-            m[memory_cell] = value
+    inputs: VecDeque<i64>,
+    // Decoded instructions by address, cleared when the program writes to the address.
+    cache: Vec<Option<Instruction>>,
+    // Instructions run() executes before it uses the cache. Programs that stop after a few
+    // instructions, like day 2, never pay for allocating it.
+    warmup: usize,
+    profile: Option<Box<Profile>>,
+}
+
+// An instruction with its parameters as they are in memory. Every instruction is decoded with
+// three parameters, unused ones are just never read.
+#[derive(Debug, Clone, Copy)]
+struct Instruction {
+    opcode: u8,
+    modes: [u8; 3],
+    parameters: [i64; 3],
+}
+
+// Instructions above this address aren't cached, so a jump far away doesn't allocate a huge cache.
+const CACHE_LIMIT: usize = 1 << 20;
+const CACHE_WARMUP: usize = 1000;
+
+// A clone starts without a cache, like a new machine. That is cheaper for clones that only run
+// a few instructions, like the droids of day 15.
+impl Clone for Intcode {
+    fn clone(&self) -> Self {
+        Intcode {
+            memory: self.memory.clone(),
+            ip: self.ip,
+            relative_base: self.relative_base,
+            inputs: self.inputs.clone(),
+            cache: vec![],
+            warmup: CACHE_WARMUP,
+            profile: self.profile.clone(),
         }
-        pm => panic!(format!("unknown write parameter mode '{}'", pm)),
-    };
+    }
 }
 
-fn c_internal(
-    m: Vec<i64>,
-    get_input: &dyn Fn(usize) -> i64,
-    post_output: &dyn Fn(&mut Vec<i64>, i64),
-) -> (Vec<i64>, Vec<i64>) {
-    let mut m = m;
-
-    let mut input_index = 0;
-    let mut outputs = vec![];
-
-    let mut rb = 0;
-    let mut i = 0;
-    while i <= m.len() {
-        let (ins, pm1, pm2, pm3) = intcode_parser(m[i]);
-        match ins {
-            99 => break,
+// The cache and the profile don't count, the cache is the same for equal memory.
+impl PartialEq for Intcode {
+    fn eq(&self, other: &Self) -> bool {
+        self.memory == other.memory
+            && self.ip == other.ip
+            && self.relative_base == other.relative_base
+            && self.inputs == other.inputs
+    }
+}
+
+impl Eq for Intcode {}
+
+impl Intcode {
+    pub fn new(memory: impl Into<Memory>) -> Self {
+        Intcode {
+            memory: memory.into(),
+            ip: 0,
+            relative_base: 0,
+            inputs: VecDeque::new(),
+            cache: vec![],
+            warmup: CACHE_WARMUP,
+            profile: None,
+        }
+    }
+
+    pub fn push_input(&mut self, value: i64) {
+        self.inputs.push_back(value);
+    }
+
+    pub fn extend_input(&mut self, values: impl IntoIterator<Item = i64>) {
+        self.inputs.extend(values);
+    }
+
+    pub fn pending_inputs(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    // Every address up to the highest one written, so after a write to a huge address this
+    // needs as much space. memory() keeps the memory sparse.
+    pub fn into_memory(self) -> Vec<i64> {
+        self.memory.into_vec()
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn relative_base(&self) -> i64 {
+        self.relative_base
+    }
+
+    // Counts executed instructions from now on, see profile::Profile.
+    pub fn enable_profiling(&mut self) {
+        self.profile.get_or_insert_with(Box::default);
+    }
+
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_deref()
+    }
+
+    // Returns the profile so far and stops profiling.
+    pub fn take_profile(&mut self) -> Option<Profile> {
+        self.profile.take().map(|profile| *profile)
+    }
+
+    fn error(&self, reason: Reason) -> IntcodeError {
+        IntcodeError {
+            ip: self.ip,
+            opcode: self.memory.get(self.ip),
+            reason,
+        }
+    }
+
+    #[inline(always)]
+    fn read(&self, parameter: i64, parameter_mode: i64) -> Result<i64, Reason> {
+        Ok(match parameter_mode {
+            0 => self.memory.get(address(parameter)?),
+            1 => parameter,
+            2 => self.memory.get(address(checked(
+                self.relative_base.checked_add(parameter),
+            )?)?),
+            pm => return Err(Reason::InvalidMode(pm)),
+        })
+    }
+
+    #[inline(always)]
+    fn write(&mut self, parameter: i64, parameter_mode: i64, value: i64) -> Result<(), Reason> {
+        let target = match parameter_mode {
+            0 => address(parameter)?,
+            1 => return Err(Reason::WriteInImmediateMode),
+            2 => address(checked(self.relative_base.checked_add(parameter))?)?,
+            pm => return Err(Reason::InvalidMode(pm)),
+        };
+        self.memory.set(target, value);
+        // The cell might be the opcode or a parameter of a cached instruction
+        if target.saturating_sub(3) < self.cache.len() {
+            let cached = self.cache.len().min(target + 1);
+            for instruction in &mut self.cache[target.saturating_sub(3)..cached] {
+                *instruction = None;
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn decode(&self) -> Result<Instruction, Reason> {
+        let [intcode, p1, p2, p3] = match self.memory.dense().get(self.ip..self.ip + 4) {
+            Some(&[intcode, p1, p2, p3]) => [intcode, p1, p2, p3],
+            _ => [0, 1, 2, 3].map(|i| self.memory.get(self.ip + i)),
+        };
+        let (opcode, pm1, pm2, pm3) = intcode_parser(intcode)?;
+        // The parser only returns opcodes below 100 and modes up to 2
+        Ok(Instruction {
+            opcode: opcode as u8,
+            modes: [pm1 as u8, pm2 as u8, pm3 as u8],
+            parameters: [p1, p2, p3],
+        })
+    }
+
+    // Decodes the instruction at ip through the cache.
+    #[inline(always)]
+    fn fetch(&mut self) -> Result<Instruction, Reason> {
+        if let Some(Some(instruction)) = self.cache.get(self.ip) {
+            return Ok(*instruction);
+        }
+        let instruction = self.decode()?;
+        if self.ip < CACHE_LIMIT {
+            if self.cache.len() <= self.ip {
+                let len = self.memory.len().clamp(self.ip + 1, CACHE_LIMIT);
+                self.cache.resize(len, None);
+            }
+            self.cache[self.ip] = Some(instruction);
+        }
+        Ok(instruction)
+    }
+
+    fn jump(&mut self, target: i64) -> Result<(), Reason> {
+        if target < 0 {
+            return Err(Reason::NegativeAddress(target));
+        }
+        self.ip = target as usize;
+        Ok(())
+    }
+
+    // Executes the instruction at ip. Returns a state if the machine has to stop there.
+    // On errors ip is left at the failing instruction.
+    #[inline(always)]
+    fn execute(&mut self, instruction: Instruction) -> Result<Option<State>, Reason> {
+        let Instruction {
+            opcode,
+            modes,
+            parameters: [p1, p2, p3],
+        } = instruction;
+        let [pm1, pm2, pm3] = modes.map(i64::from);
+        match opcode {
+            99 => return Ok(Some(State::Halted)),
             1 => {
                 // add
-                let operand_1 = read_value(&mut m, i + 1, pm1, rb);
-                let operand_2 = read_value(&mut m, i + 2, pm2, rb);
-                write_value(&mut m, i + 3, pm3, rb, operand_1 + operand_2);
-                i += 4;
+                let operand_1 = self.read(p1, pm1)?;
+                let operand_2 = self.read(p2, pm2)?;
+                self.write(p3, pm3, checked(operand_1.checked_add(operand_2))?)?;
+                self.ip += 4;
             }
             2 => {
                 // mul
-                let operand_1 = read_value(&mut m, i + 1, pm1, rb);
-                let operand_2 = read_value(&mut m, i + 2, pm2, rb);
-                write_value(&mut m, i + 3, pm3, rb, operand_1 * operand_2);
-                i += 4;
+                let operand_1 = self.read(p1, pm1)?;
+                let operand_2 = self.read(p2, pm2)?;
+                self.write(p3, pm3, checked(operand_1.checked_mul(operand_2))?)?;
+                self.ip += 4;
             }
             3 => {
                 // input
-                write_value(&mut m, i + 1, pm1, rb, get_input(input_index));
-                input_index += 1;
-                i += 2;
+                match self.inputs.front() {
+                    Some(&input) => self.write(p1, pm1, input)?,
+                    None => return Ok(Some(State::NeedsInput)),
+                }
+                self.inputs.pop_front();
+                self.ip += 2;
             }
             4 => {
                 // output
-                post_output(&mut outputs, read_value(&mut m, i + 1, pm1, rb));
-                i += 2;
+                let output = self.read(p1, pm1)?;
+                self.ip += 2;
+                return Ok(Some(State::Output(output)));
             }
             5 => {
                 // jump-if-true
-                if read_value(&mut m, i + 1, pm1, rb) != 0 {
-                    i = read_value(&mut m, i + 2, pm2, rb) as usize;
+                if self.read(p1, pm1)? != 0 {
+                    let target = self.read(p2, pm2)?;
+                    self.jump(target)?;
                 } else {
-                    i += 3;
+                    self.ip += 3;
                 }
             }
             6 => {
                 // jump-if-false
-                if read_value(&mut m, i + 1, pm1, rb) == 0 {
-                    i = read_value(&mut m, i + 2, pm2, rb) as usize;
+                if self.read(p1, pm1)? == 0 {
+                    let target = self.read(p2, pm2)?;
+                    self.jump(target)?;
                 } else {
-                    i += 3;
+                    self.ip += 3;
                 }
             }
             7 => {
                 // less than
-                let comparator_l = read_value(&mut m, i + 1, pm1, rb);
-                let comparator_r = read_value(&mut m, i + 2, pm2, rb);
-                write_value(
-                    &mut m,
-                    i + 3,
-                    pm3,
-                    rb,
-                    if comparator_l < comparator_r { 1 } else { 0 },
-                );
-                i += 4;
+                let comparator_l = self.read(p1, pm1)?;
+                let comparator_r = self.read(p2, pm2)?;
+                self.write(p3, pm3, (comparator_l < comparator_r) as i64)?;
+                self.ip += 4;
             }
             8 => {
                 // equals
-                let comparator_l = read_value(&mut m, i + 1, pm1, rb);
-                let comparator_r = read_value(&mut m, i + 2, pm2, rb);
-                write_value(
-                    &mut m,
-                    i + 3,
-                    pm3,
-                    rb,
-                    if comparator_l == comparator_r { 1 } else { 0 },
-                );
-                i += 4;
+                let comparator_l = self.read(p1, pm1)?;
+                let comparator_r = self.read(p2, pm2)?;
+                self.write(p3, pm3, (comparator_l == comparator_r) as i64)?;
+                self.ip += 4;
             }
             9 => {
                 // relative base offset
-                rb += read_value(&mut m, i + 1, pm1, rb);
-                i += 2;
+                let offset = self.read(p1, pm1)?;
+                self.relative_base = checked(self.relative_base.checked_add(offset))?;
+                self.ip += 2;
+            }
+            _ => return Err(Reason::InvalidOpcode),
+        }
+        Ok(None)
+    }
+
+    // Executes the instruction at ip and counts it in the profile once it succeeded.
+    fn execute_profiled(&mut self) -> Result<Option<State>, Reason> {
+        let ip = self.ip;
+        let instruction = self.fetch()?;
+        let opcode = instruction.opcode;
+        let condition = match opcode {
+            5 | 6 => Some(self.read(instruction.parameters[0], instruction.modes[0].into())?),
+            _ => None,
+        };
+        let state = self.execute(instruction)?;
+        if let Some(profile) = &mut self.profile {
+            // An input instruction without input runs again once there is some
+            if state != Some(State::NeedsInput) {
+                profile.instruction(ip, opcode.into());
+            }
+            if let Some(condition) = condition {
+                profile.branch(ip, opcode.into(), (condition != 0) == (opcode == 5));
+            }
+        }
+        Ok(state)
+    }
+
+    // Executes a single instruction, an input instruction without input stays at ip.
+    pub fn step(&mut self) -> Result<Option<State>, IntcodeError> {
+        let result = if self.profile.is_some() {
+            self.execute_profiled()
+        } else {
+            self.fetch()
+                .and_then(|instruction| self.execute(instruction))
+        };
+        result.map_err(|reason| self.error(reason))
+    }
+
+    // Executes instructions until the program halts, outputs a value or wants an input that
+    // hasn't been pushed yet. Running a halted machine again returns Halted right away.
+    pub fn run(&mut self) -> Result<State, IntcodeError> {
+        if self.profile.is_some() {
+            loop {
+                if let Some(state) = self.step()? {
+                    return Ok(state);
+                }
+            }
+        }
+        // Short programs are done before the cache would pay off, so the first instructions
+        // are decoded every time without looking at it
+        while self.warmup > 0 {
+            self.warmup -= 1;
+            match self
+                .decode()
+                .and_then(|instruction| self.execute(instruction))
+            {
+                Ok(None) => {}
+                Ok(Some(state)) => return Ok(state),
+                Err(reason) => return Err(self.error(reason)),
+            }
+        }
+        loop {
+            match self
+                .fetch()
+                .and_then(|instruction| self.execute(instruction))
+            {
+                Ok(None) => {}
+                Ok(Some(state)) => return Ok(state),
+                Err(reason) => return Err(self.error(reason)),
+            }
+        }
+    }
+
+    // Runs until the program halts and returns everything it printed.
+    pub fn run_to_halt(&mut self) -> Result<Vec<i64>, IntcodeError> {
+        let mut outputs = vec![];
+        loop {
+            match self.run()? {
+                State::Output(output) => outputs.push(output),
+                State::Halted => return Ok(outputs),
+                State::NeedsInput => return Err(self.error(Reason::InputExhausted)),
             }
-            ins => panic!(format!("unknown instruction '{}'", ins)),
         }
     }
-    (m, outputs)
 }
 
-pub fn compute(m: Vec<i64>, inputs: Vec<i64>) -> (Vec<i64>, Vec<i64>) {
-    let (m, outputs) = c_internal(m, &|i| inputs[i], &|outputs, o| outputs.push(o));
+// Returns the memory the program left behind and its outputs.
+pub fn compute(m: impl Into<Memory>, inputs: Vec<i64>) -> Result<(Memory, Vec<i64>), IntcodeError> {
+    let mut machine = Intcode::new(m);
+    machine.extend_input(inputs);
+    let outputs = machine.run_to_halt()?;
 
-    (m, outputs)
+    Ok((machine.memory, outputs))
 }
 
 pub fn compute_threaded(
     m: Vec<i64>,
     input_rx: Option<Receiver<i64>>,
     output_tx: Option<Sender<i64>>,
-    return_tx: Option<Sender<(Vec<i64>, Receiver<i64>, Sender<i64>)>>,
+    return_tx: Option<Sender<(Memory, Receiver<i64>, Sender<i64>)>>,
 ) -> (
-    JoinHandle<()>,
+    JoinHandle<Result<(), IntcodeError>>,
     Option<Sender<i64>>,
     Option<Receiver<i64>>,
-    Option<Receiver<(Vec<i64>, Receiver<i64>, Sender<i64>)>>,
+    Option<Receiver<(Memory, Receiver<i64>, Sender<i64>)>>,
 ) {
     let (maybe_input_tx, input_rx) = if let Some(input_rx) = input_rx {
         (None, input_rx)
@@ -303,27 +756,26 @@ pub fn compute_threaded(
     let (return_tx, maybe_return_rx) = if let Some(return_tx) = return_tx {
         (return_tx, None)
     } else {
-        let (return_tx, return_rx) = mpsc::channel::<(Vec<i64>, Receiver<i64>, Sender<i64>)>();
+        let (return_tx, return_rx) = mpsc::channel::<(Memory, Receiver<i64>, Sender<i64>)>();
         (return_tx, Some(return_rx))
     };
     (
         thread::spawn(move || {
-            let (m, _) = c_internal(
-                m,
-                &|_| {
-                    input_rx
-                        .recv()
-                        .expect("Tried to receive an input but it failed")
-                },
-                &|_, o| {
-                    output_tx
+            let mut machine = Intcode::new(m);
+            loop {
+                match machine.run()? {
+                    State::NeedsInput => match input_rx.recv() {
+                        Ok(input) => machine.push_input(input),
+                        Err(_) => return Err(machine.error(Reason::InputExhausted)),
+                    },
+                    State::Output(o) => output_tx
                         .send(o)
-                        .expect("Tried to send an output but it failed")
-                },
-            );
-            match return_tx.send((m, input_rx, output_tx)) {
-                _ => {}
+                        .expect("Tried to send an output but it failed"),
+                    State::Halted => break,
+                }
             }
+            let _ = return_tx.send((machine.memory, input_rx, output_tx));
+            Ok(())
         }),
         maybe_input_tx,
         maybe_output_rx,
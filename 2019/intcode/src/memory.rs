@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::ops::Index;
+
+const PAGE_BITS: usize = 10;
+pub const PAGE_SIZE: usize = 1 << PAGE_BITS;
+// Addresses below this are kept in one vector that grows a page at a time, the pages above are
+// in a map, so a single write to a huge address costs one page instead of everything up to it.
+const DENSE_LIMIT: usize = 1 << 20;
+
+type Page = Box<[i64; PAGE_SIZE]>;
+
+// Sparse memory of an Intcode machine, made of pages that are allocated on the first write.
+// Untouched addresses read as 0.
+#[derive(Debug, Clone, Default)]
+pub struct Memory {
+    dense: Vec<i64>,
+    sparse_pages: BTreeMap<usize, Page>,
+    len: usize,
+}
+
+impl Memory {
+    #[inline(always)]
+    pub fn get(&self, address: usize) -> i64 {
+        if address < self.dense.len() {
+            self.dense[address]
+        } else if self.sparse_pages.is_empty() {
+            0
+        } else {
+            self.sparse_get(address)
+        }
+    }
+
+    #[cold]
+    fn sparse_get(&self, address: usize) -> i64 {
+        self.sparse_pages
+            .get(&(address >> PAGE_BITS))
+            .map_or(0, |page| page[address & (PAGE_SIZE - 1)])
+    }
+
+    // The cells below the sparse pages, shorter than len if the rest is sparse or untouched.
+    #[inline(always)]
+    pub(crate) fn dense(&self) -> &[i64] {
+        &self.dense
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, address: usize, value: i64) {
+        if address < self.dense.len() {
+            self.dense[address] = value;
+        } else if address < DENSE_LIMIT {
+            self.dense.resize((address | (PAGE_SIZE - 1)) + 1, 0);
+            self.dense[address] = value;
+        } else {
+            self.sparse_pages
+                .entry(address >> PAGE_BITS)
+                .or_insert_with(|| Box::new([0; PAGE_SIZE]))[address & (PAGE_SIZE - 1)] = value;
+        }
+        self.len = self.len.max(address + 1);
+    }
+
+    // One past the highest address that was loaded or written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Trailing zeros don't need a page, but still count for len.
+    pub(crate) fn grow(&mut self, len: usize) {
+        self.len = self.len.max(len);
+    }
+
+    // Allocated pages as (first address, cells) ordered by address.
+    pub fn pages(&self) -> Vec<(usize, &[i64])> {
+        let mut pages = self
+            .dense
+            .chunks(PAGE_SIZE)
+            .enumerate()
+            .map(|(i, page)| (i << PAGE_BITS, page))
+            .chain(
+                self.sparse_pages
+                    .iter()
+                    .map(|(&i, page)| (i << PAGE_BITS, &page[..])),
+            )
+            .collect::<Vec<_>>();
+        pages.sort_unstable_by_key(|&(address, _)| address);
+        pages
+    }
+
+    // Everything up to len, including the zeros in between.
+    pub fn to_vec(&self) -> Vec<i64> {
+        self.clone().into_vec()
+    }
+
+    pub fn into_vec(self) -> Vec<i64> {
+        let mut cells = self.dense;
+        cells.resize(self.len, 0);
+        if self.sparse_pages.is_empty() {
+            return cells;
+        }
+        for (page, values) in self.sparse_pages {
+            let start = page << PAGE_BITS;
+            let end = (start + PAGE_SIZE).min(self.len);
+            cells[start..end].copy_from_slice(&values[..end - start]);
+        }
+        cells
+    }
+}
+
+impl From<Vec<i64>> for Memory {
+    fn from(cells: Vec<i64>) -> Self {
+        if cells.len() <= DENSE_LIMIT {
+            return Memory {
+                len: cells.len(),
+                dense: cells,
+                sparse_pages: BTreeMap::new(),
+            };
+        }
+        let mut memory = Memory {
+            len: cells.len(),
+            ..Memory::default()
+        };
+        for (address, value) in cells.into_iter().enumerate() {
+            memory.set(address, value);
+        }
+        memory
+    }
+}
+
+// For reading only, like get.
+impl Index<usize> for Memory {
+    type Output = i64;
+
+    #[inline(always)]
+    fn index(&self, address: usize) -> &i64 {
+        match self.dense.get(address) {
+            Some(value) => value,
+            None => self
+                .sparse_pages
+                .get(&(address >> PAGE_BITS))
+                .map_or(&0, |page| &page[address & (PAGE_SIZE - 1)]),
+        }
+    }
+}
+
+// Equal if every address reads the same, no matter which pages happen to be allocated.
+impl PartialEq for Memory {
+    fn eq(&self, other: &Self) -> bool {
+        let nonzero = |memory: &Memory| {
+            memory
+                .pages()
+                .into_iter()
+                .flat_map(|(start, cells)| {
+                    cells
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, &value)| value != 0)
+                        .map(move |(i, &value)| (start + i, value))
+                })
+                .collect::<Vec<_>>()
+        };
+        self.len == other.len && nonzero(self) == nonzero(other)
+    }
+}
+
+impl Eq for Memory {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_tests() {
+        let mut memory = Memory::from(vec![1, 2, 3]);
+        assert_eq!(memory.len(), 3);
+        assert_eq!(memory.get(2), 3);
+        assert_eq!(memory.get(5000), 0);
+        assert_eq!(memory.len(), 3);
+
+        memory.set(1 << 40, 7);
+        memory.set(PAGE_SIZE + 1, 5);
+        assert_eq!(memory.get(1 << 40), 7);
+        assert_eq!(memory.get((1 << 40) + 1), 0);
+        assert_eq!(
+            (memory[1], memory[PAGE_SIZE + 1], memory[1 << 40]),
+            (2, 5, 7)
+        );
+        assert_eq!(memory.len(), (1 << 40) + 1);
+        assert_eq!(
+            memory
+                .pages()
+                .iter()
+                .map(|&(address, _)| address)
+                .collect::<Vec<_>>(),
+            vec![0, PAGE_SIZE, 1 << 40]
+        );
+
+        let mut other = Memory::from(vec![1, 2, 3, 0]);
+        assert_ne!(memory, other);
+        other.set(1 << 40, 7);
+        other.set(PAGE_SIZE + 1, 5);
+        other.set(PAGE_SIZE * 3, 0);
+        assert_eq!(memory, other);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{Intcode, IntcodeError, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    // Every machine feeds the next one, the outputs of the last one are returned as events.
+    Pipeline,
+    // Like a pipeline, but the last machine also feeds the first one.
+    Ring,
+    // Machines send (address, x, y) packets to each other. Waiting for input with an empty queue
+    // reads -1, packets to addresses outside the network are returned as events.
+    Bus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Output(i64),
+    Packet { to: i64, x: i64, y: i64 },
+    // A whole round on the bus went by without a single packet.
+    Idle,
+    // A whole round went by without any machine getting further.
+    Deadlock,
+    Halted,
+}
+
+// Runs machines cooperatively on one thread. Machines take turns in the order of their
+// index and each turn lasts until the machine waits for input, so results are reproducible.
+pub struct Network {
+    machines: Vec<Intcode>,
+    topology: Topology,
+    halted: Vec<bool>,
+    // Outputs of each machine that don't make up a whole packet yet.
+    partial_packets: Vec<Vec<i64>>,
+    current: usize,
+    in_turn: bool,
+    turns_this_round: usize,
+    progress: bool,
+}
+
+impl Network {
+    pub fn new(machines: Vec<Intcode>, topology: Topology) -> Self {
+        let n = machines.len();
+        Network {
+            machines,
+            topology,
+            halted: vec![false; n],
+            partial_packets: vec![vec![]; n],
+            current: 0,
+            in_turn: false,
+            turns_this_round: 0,
+            progress: false,
+        }
+    }
+
+    // A bus of copies of the program that get their address as the first input.
+    pub fn bus(memory: &[i64], size: usize) -> Self {
+        let machines = (0..size)
+            .map(|address| {
+                let mut machine = Intcode::new(memory.to_vec());
+                machine.push_input(address as i64);
+                machine
+            })
+            .collect();
+        Network::new(machines, Topology::Bus)
+    }
+
+    pub fn machines(&self) -> &[Intcode] {
+        &self.machines
+    }
+
+    pub fn push_input(&mut self, machine: usize, value: i64) {
+        self.machines[machine].push_input(value);
+    }
+
+    pub fn send(&mut self, to: usize, x: i64, y: i64) {
+        self.machines[to].extend_input(vec![x, y]);
+    }
+
+    fn next_turn(&mut self) {
+        self.current = (self.current + 1) % self.machines.len();
+        self.in_turn = false;
+        self.turns_this_round += 1;
+    }
+
+    fn route(&mut self, from: usize, value: i64) -> Option<Event> {
+        let n = self.machines.len();
+        match self.topology {
+            Topology::Pipeline if from + 1 < n => {
+                self.machines[from + 1].push_input(value);
+                None
+            }
+            Topology::Pipeline => Some(Event::Output(value)),
+            Topology::Ring => {
+                self.machines[(from + 1) % n].push_input(value);
+                Some(Event::Output(value)).filter(|_| from == n - 1)
+            }
+            Topology::Bus => {
+                self.partial_packets[from].push(value);
+                match self.partial_packets[from][..] {
+                    [to, x, y] => {
+                        self.partial_packets[from].clear();
+                        if to >= 0 && (to as usize) < n {
+                            self.send(to as usize, x, y);
+                            None
+                        } else {
+                            Some(Event::Packet { to, x, y })
+                        }
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    // Runs the machines until there is something to report.
+    pub fn run(&mut self) -> Result<Event, IntcodeError> {
+        let n = self.machines.len();
+        loop {
+            if self.halted.iter().all(|&halted| halted) {
+                return Ok(Event::Halted);
+            }
+            if self.turns_this_round == n {
+                self.turns_this_round = 0;
+                if !std::mem::take(&mut self.progress) {
+                    return Ok(match self.topology {
+                        Topology::Bus => Event::Idle,
+                        _ => Event::Deadlock,
+                    });
+                }
+            }
+
+            let i = self.current;
+            if self.halted[i] {
+                self.next_turn();
+                continue;
+            }
+            if !self.in_turn && self.topology == Topology::Bus {
+                if self.machines[i].pending_inputs() == 0 {
+                    self.machines[i].push_input(-1);
+                } else {
+                    self.progress = true;
+                }
+            }
+            self.in_turn = true;
+
+            let ip = self.machines[i].ip();
+            match self.machines[i].run()? {
+                State::Output(value) => {
+                    self.progress = true;
+                    if let Some(event) = self.route(i, value) {
+                        return Ok(event);
+                    }
+                }
+                State::NeedsInput => {
+                    if self.topology != Topology::Bus && self.machines[i].ip() != ip {
+                        self.progress = true;
+                    }
+                    self.next_turn();
+                }
+                State::Halted => {
+                    self.halted[i] = true;
+                    self.next_turn();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::assemble;
+
+    fn amplifiers(memory: &[i64], phases: &[i64], topology: Topology) -> Network {
+        let machines = phases
+            .iter()
+            .map(|&phase| {
+                let mut machine = Intcode::new(memory.to_vec());
+                machine.push_input(phase);
+                machine
+            })
+            .collect();
+        let mut network = Network::new(machines, topology);
+        network.push_input(0, 0);
+        network
+    }
+
+    #[test]
+    fn pipeline_tests() {
+        let memory = vec![
+            3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+        ];
+        let mut network = amplifiers(&memory, &[4, 3, 2, 1, 0], Topology::Pipeline);
+        assert_eq!(network.run(), Ok(Event::Output(43210)));
+        assert_eq!(network.run(), Ok(Event::Halted));
+        assert_eq!(network.run(), Ok(Event::Halted));
+    }
+
+    #[test]
+    fn ring_tests() {
+        let memory = vec![
+            3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1,
+            28, 1005, 28, 6, 99, 0, 0, 5,
+        ];
+        let mut network = amplifiers(&memory, &[9, 8, 7, 6, 5], Topology::Ring);
+        let mut outputs = vec![];
+        while let Event::Output(value) = network.run().unwrap() {
+            outputs.push(value);
+        }
+        assert_eq!(outputs.len(), 5);
+        assert_eq!(outputs.last(), Some(&139629729));
+        assert!(network.machines().iter().all(|m| m.pending_inputs() <= 1));
+    }
+
+    #[test]
+    fn deadlock_tests() {
+        // Both wait for the other one to start
+        let memory = assemble("loop: in [x]\nout [x]\njt 1, loop\nx: data 0").unwrap();
+        let machines = vec![Intcode::new(memory.clone()), Intcode::new(memory)];
+        let mut network = Network::new(machines, Topology::Ring);
+        assert_eq!(network.run(), Ok(Event::Deadlock));
+        assert_eq!(network.run(), Ok(Event::Deadlock));
+
+        network.push_input(1, 42);
+        assert_eq!(network.run(), Ok(Event::Output(42)));
+        for _ in 0..3 {
+            assert_eq!(network.run(), Ok(Event::Output(42)));
+        }
+    }
+
+    #[test]
+    fn bus_tests() {
+        // Machine 0 sends a packet to 1, every received packet goes on to 255 with x += address
+        let memory = assemble(
+            "
+                    in [addr]
+                    jt [addr], loop
+                    out 1
+                    out 7
+                    out 8
+            loop:   in [x]
+                    eq [x], -1, [t]
+                    jt [t], loop
+                    in [y]
+                    out 255
+                    add [x], [addr], [x]
+                    out [x]
+                    out [y]
+                    jt 1, loop
+            addr:   data 0
+            x:      data 0
+            y:      data 0
+            t:      data 0
+            ",
+        )
+        .unwrap();
+        let mut network = Network::bus(&memory, 2);
+        assert_eq!(
+            network.run(),
+            Ok(Event::Packet {
+                to: 255,
+                x: 8,
+                y: 8
+            })
+        );
+        assert_eq!(network.run(), Ok(Event::Idle));
+        assert_eq!(network.run(), Ok(Event::Idle));
+
+        // What a NAT does
+        network.send(0, 1, 2);
+        assert_eq!(
+            network.run(),
+            Ok(Event::Packet {
+                to: 255,
+                x: 1,
+                y: 2
+            })
+        );
+        assert_eq!(network.run(), Ok(Event::Idle));
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use crate::asm::mnemonic;
+
+fn name(opcode: i64) -> String {
+    mnemonic(opcode).map_or_else(|| opcode.to_string(), str::to_string)
+}
+
+fn share(count: u64, total: u64) -> f64 {
+    100.0 * count as f64 / total.max(1) as f64
+}
+
+// Counts of executed instructions, collected by a machine with profiling enabled.
+// Instructions are counted by address and opcode, since self-modifying programs can run
+// different instructions at the same address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub instructions: HashMap<(usize, i64), u64>,
+    // (taken, not taken) of the conditional jumps by address and opcode
+    pub branches: HashMap<(usize, i64), (u64, u64)>,
+}
+
+impl Profile {
+    pub(crate) fn instruction(&mut self, address: usize, opcode: i64) {
+        *self.instructions.entry((address, opcode)).or_insert(0) += 1;
+    }
+
+    pub(crate) fn branch(&mut self, address: usize, opcode: i64, taken: bool) {
+        let (t, n) = self.branches.entry((address, opcode)).or_insert((0, 0));
+        if taken {
+            *t += 1;
+        } else {
+            *n += 1;
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.instructions.values().sum()
+    }
+
+    // Executed instructions by opcode, most frequent first.
+    pub fn opcodes(&self) -> Vec<(i64, u64)> {
+        let mut opcodes = HashMap::new();
+        for (&(_, opcode), &count) in &self.instructions {
+            *opcodes.entry(opcode).or_insert(0) += count;
+        }
+        let mut opcodes = opcodes.into_iter().collect::<Vec<_>>();
+        opcodes.sort_unstable_by_key(|&(opcode, count)| (u64::MAX - count, opcode));
+        opcodes
+    }
+
+    // (address, opcode, count), most frequent first.
+    pub fn hot_addresses(&self) -> Vec<(usize, i64, u64)> {
+        let mut addresses = self
+            .instructions
+            .iter()
+            .map(|(&(address, opcode), &count)| (address, opcode, count))
+            .collect::<Vec<_>>();
+        addresses
+            .sort_unstable_by_key(|&(address, opcode, count)| (u64::MAX - count, address, opcode));
+        addresses
+    }
+
+    // The opcode histogram, the most executed addresses and the outcomes of the conditional
+    // jumps among them.
+    pub fn table(&self, limit: usize) -> String {
+        let total = self.total();
+        let mut table = format!("{} instructions\n\nopcode        count   share\n", total);
+        for (opcode, count) in self.opcodes() {
+            table += &format!(
+                "{:<6}  {:>11}  {:>5.1}%\n",
+                name(opcode),
+                count,
+                share(count, total)
+            );
+        }
+        table += "\naddress  opcode        count   share      taken  not taken\n";
+        for (address, opcode, count) in self.hot_addresses().into_iter().take(limit) {
+            table += &format!(
+                "{:>7}  {:<6}  {:>11}  {:>5.1}%",
+                address,
+                name(opcode),
+                count,
+                share(count, total)
+            );
+            if let Some((taken, not_taken)) = self.branches.get(&(address, opcode)) {
+                table += &format!("  {:>9}  {:>9}", taken, not_taken);
+            }
+            table += "\n";
+        }
+        table
+    }
+
+    // Folded stacks as read by flamegraph.pl or inferno, one `intcode;<opcode>;<address> count`
+    // line per instruction. Intcode has no call stack, so the frames group by opcode.
+    pub fn folded(&self) -> String {
+        let mut instructions = self.instructions.iter().collect::<Vec<_>>();
+        instructions.sort_unstable_by_key(|&(&(address, opcode), _)| (opcode, address));
+        instructions
+            .into_iter()
+            .map(|(&(address, opcode), count)| {
+                format!("intcode;{};{} {}\n", name(opcode), address, count)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::assemble;
+    use crate::{Intcode, State};
+
+    #[test]
+    fn profile_tests() {
+        // Counts down from 3
+        let memory = assemble(
+            "
+            loop:   add [n], -1, [n]
+                    jt [n], loop
+                    out [n]
+                    hlt
+            n:      data 3
+            ",
+        )
+        .unwrap();
+        let mut machine = Intcode::new(memory);
+        assert_eq!(machine.profile(), None);
+        machine.enable_profiling();
+        assert_eq!(machine.run(), Ok(State::Output(0)));
+        assert_eq!(machine.run(), Ok(State::Halted));
+
+        let profile = machine.take_profile().unwrap();
+        assert_eq!(machine.profile(), None);
+        assert_eq!(profile.total(), 8);
+        assert_eq!(profile.opcodes(), vec![(1, 3), (5, 3), (4, 1), (99, 1)]);
+        assert_eq!(profile.hot_addresses()[..2], [(0, 1, 3), (4, 5, 3)]);
+        assert_eq!(profile.branches[&(4, 5)], (2, 1));
+
+        let table = profile.table(2);
+        assert!(table.starts_with("8 instructions\n"), "{}", table);
+        assert!(
+            table.contains("\nadd               3   37.5%\n"),
+            "{}",
+            table
+        );
+        assert!(
+            table.ends_with("\n      4  jt                3   37.5%          2          1\n"),
+            "{}",
+            table
+        );
+        assert_eq!(
+            profile.folded(),
+            "intcode;add;0 3\nintcode;out;7 1\nintcode;jt;4 3\nintcode;hlt;9 1\n"
+        );
+
+        // Waiting for input doesn't count as executing the input instruction
+        let mut machine = Intcode::new(vec![3, 0, 99]);
+        machine.enable_profiling();
+        assert_eq!(machine.run(), Ok(State::NeedsInput));
+        assert_eq!(machine.profile().unwrap().total(), 0);
+        machine.push_input(1);
+        assert_eq!(machine.run(), Ok(State::Halted));
+        assert_eq!(machine.profile().unwrap().opcodes(), vec![(3, 1), (99, 1)]);
+
+        // Neither does an instruction that fails
+        let mut machine = Intcode::new(vec![104, 1, 1005, -1, 0]);
+        machine.enable_profiling();
+        assert_eq!(machine.run(), Ok(State::Output(1)));
+        assert!(machine.run().is_err());
+        let profile = machine.profile().unwrap();
+        assert_eq!(profile.opcodes(), vec![(4, 1)]);
+        assert!(profile.branches.is_empty());
+
+        // Rows only show the branches of their own opcode, a self-modifying program can run
+        // a jump and an add at the same address
+        let profile = Profile {
+            instructions: HashMap::from([((0, 5), 2), ((0, 1), 1)]),
+            branches: HashMap::from([((0, 5), (1, 1))]),
+        };
+        assert_eq!(
+            profile.table(2).lines().skip(7).collect::<Vec<_>>(),
+            [
+                "      0  jt                2   66.7%          1          1",
+                "      0  add               1   33.3%",
+            ]
+        );
+    }
+}
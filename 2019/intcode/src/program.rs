@@ -0,0 +1,138 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::Intcode;
+
+#[derive(Debug)]
+pub enum ProgramError {
+    Io(PathBuf, io::Error),
+    InvalidValue { index: usize, value: String },
+    Empty,
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgramError::Io(path, error) => write!(f, "can't read {}: {}", path.display(), error),
+            ProgramError::InvalidValue { index, value } => {
+                write!(f, "invalid value '{}' at index {}", value, index)
+            }
+            ProgramError::Empty => write!(f, "empty program"),
+        }
+    }
+}
+
+impl Error for ProgramError {}
+
+// `INPUT` can be set to run a day on another file, like in the later years. Otherwise `./input`
+// in the directory of the day is used.
+pub fn input_path() -> PathBuf {
+    match env::var("INPUT") {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => PathBuf::from("./input"),
+    }
+}
+
+// The comma separated integers of a puzzle input. Whitespace around values, line breaks
+// included, and a trailing comma are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    memory: Vec<i64>,
+}
+
+impl Program {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Program, ProgramError> {
+        let path = path.as_ref();
+        fs::read_to_string(path)
+            .map_err(|error| ProgramError::Io(path.to_path_buf(), error))?
+            .parse()
+    }
+
+    pub fn from_input() -> Result<Program, ProgramError> {
+        Program::from_path(input_path())
+    }
+
+    pub fn memory(&self) -> &[i64] {
+        &self.memory
+    }
+
+    pub fn into_memory(self) -> Vec<i64> {
+        self.memory
+    }
+
+    pub fn machine(&self) -> Intcode {
+        Intcode::new(self.memory.clone())
+    }
+}
+
+impl FromStr for Program {
+    type Err = ProgramError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = s.split(',').map(str::trim).collect::<Vec<_>>();
+        if values.last() == Some(&"") {
+            values.pop();
+        }
+        if values.is_empty() {
+            return Err(ProgramError::Empty);
+        }
+        let memory = values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                value.parse().map_err(|_| ProgramError::InvalidValue {
+                    index,
+                    value: value.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Program { memory })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    #[test]
+    fn program_tests() {
+        for source in [
+            "104,1,99",
+            "104, 1, 99\n",
+            " 104,\n1,\r\n99,\n",
+            "104,1,99,",
+        ] {
+            let program = source.parse::<Program>().unwrap();
+            assert_eq!(program.memory(), &[104, 1, 99]);
+            assert_eq!(program.machine().run(), Ok(State::Output(1)));
+        }
+    }
+
+    #[test]
+    fn program_error_tests() {
+        for (source, error) in [
+            ("", "empty program"),
+            (" \n", "empty program"),
+            ("1,,99", "invalid value '' at index 1"),
+            ("1,2\n3,99", "invalid value '2\n3' at index 1"),
+            ("1,0x10,99", "invalid value '0x10' at index 1"),
+        ] {
+            assert_eq!(source.parse::<Program>().unwrap_err().to_string(), error);
+        }
+
+        let error = Program::from_path("does/not/exist")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with("can't read does/not/exist: "),
+            "{}",
+            error
+        );
+    }
+}
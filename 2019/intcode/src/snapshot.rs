@@ -0,0 +1,187 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::memory::Memory;
+use crate::Intcode;
+
+const HEADER: &str = "intcode snapshot";
+
+fn join(values: impl Iterator<Item = i64>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn split(values: &str) -> Result<Vec<i64>, Box<dyn Error>> {
+    values
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| Ok(v.parse()?))
+        .collect()
+}
+
+// Machines are plain data, a clone is a snapshot in memory that can be run on independently.
+// These write one to text and read it back, e.g. to keep a save point between runs.
+// Memory is written as its length followed by one line per allocated page without the
+// trailing zeros, so a program that wrote to a huge address doesn't produce a huge file.
+impl Intcode {
+    pub fn to_snapshot(&self) -> String {
+        let mut snapshot = format!(
+            "{}\nip {}\nrelative_base {}\ninputs {}\nmemory {}\n",
+            HEADER,
+            self.ip,
+            self.relative_base,
+            join(self.inputs.iter().copied()),
+            self.memory.len(),
+        );
+        for (address, cells) in self.memory.pages() {
+            let used = cells.iter().rposition(|&v| v != 0).map_or(0, |i| i + 1);
+            if used > 0 {
+                snapshot += &format!("page {} {}\n", address, join(cells[..used].iter().copied()));
+            }
+        }
+        snapshot
+    }
+
+    pub fn from_snapshot(snapshot: &str) -> Result<Intcode, Box<dyn Error>> {
+        let mut lines = snapshot.lines();
+        if lines.next() != Some(HEADER) {
+            return Err("Invalid snapshot: Missing header".into());
+        }
+        let mut field = |name: &str| -> Result<&str, Box<dyn Error>> {
+            let line = lines.next().unwrap_or("");
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            if key != name {
+                return Err(
+                    format!("Invalid snapshot: Expected {} but got '{}'", name, key).into(),
+                );
+            }
+            Ok(value)
+        };
+
+        let ip = field("ip")?.parse()?;
+        let relative_base = field("relative_base")?.parse()?;
+        let inputs = split(field("inputs")?)?.into();
+        let len = field("memory")?.parse()?;
+
+        let mut memory = Memory::default();
+        for line in lines {
+            let (address, values) = match line.split_once(' ') {
+                Some(("page", page)) => page.split_once(' ').unwrap_or((page, "")),
+                _ => {
+                    return Err(
+                        format!("Invalid snapshot: Expected page but got '{}'", line).into(),
+                    )
+                }
+            };
+            let address: usize = address.parse()?;
+            let values = split(values)?;
+            // The machine can't address anything above i64::MAX
+            if address
+                .checked_add(values.len())
+                .is_none_or(|end| end > i64::MAX as usize + 1)
+            {
+                return Err("Invalid snapshot: Page beyond the address space".into());
+            }
+            for (i, value) in values.into_iter().enumerate() {
+                memory.set(address + i, value);
+            }
+        }
+        if memory.len() > len {
+            return Err("Invalid snapshot: Page beyond the memory length".into());
+        }
+        memory.grow(len);
+
+        let mut machine = Intcode::new(vec![]);
+        machine.memory = memory;
+        machine.ip = ip;
+        machine.relative_base = relative_base;
+        machine.inputs = inputs;
+        Ok(machine)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        Ok(fs::write(path, self.to_snapshot())?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Intcode, Box<dyn Error>> {
+        Intcode::from_snapshot(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use std::env;
+
+    // Adds up its inputs and prints the sum after each one
+    const ADDER: [i64; 13] = [109, 100, 3, 13, 20201, 0, 13, 0, 204, 0, 1105, 1, 2];
+
+    #[test]
+    fn snapshot_tests() {
+        let mut machine = Intcode::new(ADDER.to_vec());
+        machine.extend_input(vec![3, 4]);
+        assert_eq!(machine.run(), Ok(State::Output(3)));
+
+        let snapshot = machine.to_snapshot();
+        assert!(snapshot.starts_with("intcode snapshot\nip 10\nrelative_base 100\ninputs 4\n"));
+
+        let mut restored = Intcode::from_snapshot(&snapshot).unwrap();
+        assert_eq!(restored, machine);
+        restored.push_input(5);
+        assert_eq!(restored.run(), Ok(State::Output(7)));
+        assert_eq!(restored.run(), Ok(State::Output(12)));
+        assert_eq!(machine.run(), Ok(State::Output(7)));
+        assert_eq!(machine.run(), Ok(State::NeedsInput));
+
+        let path = env::temp_dir().join(format!("intcode_snapshot_{}", std::process::id()));
+        restored.save(&path).unwrap();
+        assert_eq!(Intcode::load(&path).unwrap(), restored);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn snapshot_error_tests() {
+        for (snapshot, error) in [
+            ("", "Invalid snapshot: Missing header"),
+            (
+                "intcode snapshot\nip 1\n",
+                "Invalid snapshot: Expected relative_base but got ''",
+            ),
+            (
+                "intcode snapshot\nip 1\nrelative_base 0\nmemory 99\ninputs\n",
+                "Invalid snapshot: Expected inputs but got 'memory'",
+            ),
+            (
+                "intcode snapshot\nip 1\nrelative_base 0\ninputs\nmemory 1,2\n",
+                "invalid digit found in string",
+            ),
+            (
+                "intcode snapshot\nip 1\nrelative_base 0\ninputs\nmemory 2\npage 0 1,x\n",
+                "invalid digit found in string",
+            ),
+            (
+                "intcode snapshot\nip 1\nrelative_base 0\ninputs\nmemory 2\n1,2\n",
+                "Invalid snapshot: Expected page but got '1,2'",
+            ),
+            (
+                "intcode snapshot\nip 1\nrelative_base 0\ninputs\nmemory 2\npage 0 1,2,3\n",
+                "Invalid snapshot: Page beyond the memory length",
+            ),
+            (
+                "intcode snapshot\nip 1\nrelative_base 0\ninputs\nmemory 2\npage 18446744073709551615 1\n",
+                "Invalid snapshot: Page beyond the address space",
+            ),
+            (
+                "intcode snapshot\nip 1\nrelative_base 0\ninputs\nmemory 2\npage 9223372036854775807 1,2\n",
+                "Invalid snapshot: Page beyond the address space",
+            ),
+        ] {
+            assert_eq!(
+                Intcode::from_snapshot(snapshot).unwrap_err().to_string(),
+                error
+            );
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use aoc_util::number_theory::crt;
 use std::fs::File;
 use std::io::prelude::*;
 use std::str::from_utf8;
@@ -27,58 +28,23 @@ fn part1(buffer: &Vec<u8>) -> u32 {
     (min - offset) * min_id
 }
 
-// TODO: Use chinese remainder theorem, otherwise this takes forever
-fn part2(buffer: &Vec<u8>, first_id_min: u64, first_id_max: u64) -> u64 {
-    let numbers: Vec<Option<u64>> = buffer
+fn part2(buffer: &Vec<u8>) -> u64 {
+    // Bus `id` departs `offset` minutes after t, so t ≡ -offset (mod id)
+    let congruences = buffer
         .split(|b| b == &b'\n')
         .skip(1)
         .next()
         .unwrap()
         .split(|b| b == &b',')
-        .map(|bytes| match bytes[0] {
-            b'x' => None,
-            _ => Some(from_utf8(bytes).unwrap().parse::<u64>().unwrap()),
-        })
-        .collect();
-
-    let initial_first_id = numbers[0].unwrap();
-    let mut next_min = first_id_min;
-    let mut trillions = 1;
-
-    loop {
-        let first_id = (next_min / initial_first_id + 1) * initial_first_id;
-        next_min = first_id;
-
-        let (_, _, ongoing) = numbers
-            .iter()
-            .enumerate()
-            .skip(1)
-            .filter_map(|(i, o)| if let Some(n) = o { Some((i, n)) } else { None })
-            .fold(
-                (first_id, 0, true),
-                |(previous_id, previous_id_index, ongoing), (index, id)| {
-                    let id = (first_id / id + 1) * id;
-                    next_min = next_min.min(id);
-                    (
-                        id,
-                        index,
-                        ongoing && id - (index - previous_id_index) as u64 == previous_id,
-                    )
-                },
-            );
-
-        if first_id > first_id_max {
-            panic!("First id over max");
-        }
-        if ongoing {
-            return first_id;
-        }
-        let new_trillions = first_id / 1000000000000;
-        if new_trillions > trillions {
-            trillions = new_trillions;
-            println!("first_id > {}_000_000_000_000", trillions);
-        }
-    }
+        .enumerate()
+        .filter(|(_, bytes)| bytes[0] != b'x')
+        .map(|(offset, bytes)| {
+            let id = from_utf8(bytes).unwrap().parse::<i128>().unwrap();
+            (-(offset as i128), id)
+        });
+
+    let (t, _) = crt(congruences).expect("Bus schedule has no solution");
+    t as u64
 }
 
 fn main() {
@@ -92,11 +58,7 @@ fn main() {
 
     let s2 = Instant::now();
 
-    println!(
-        "part2: {} ({}µs)",
-        part2(&buffer, 100000000000000, u64::MAX),
-        s2.elapsed().as_micros()
-    );
+    println!("part2: {} ({}µs)", part2(&buffer), s2.elapsed().as_micros());
 
     println!("Time: {}µs", s1.elapsed().as_micros());
 }
@@ -109,9 +71,7 @@ fn test_part2_example1() {
             &"
 7,13,x,x,59,x,31,19"
                 .as_bytes()
-                .to_vec(),
-            1000000,
-            1068781
+                .to_vec()
         )
     );
 }
@@ -124,9 +84,7 @@ fn test_part2_example2() {
             &"
 17,x,13,19"
                 .as_bytes()
-                .to_vec(),
-            3000,
-            3417
+                .to_vec()
         )
     );
 }
@@ -139,9 +97,7 @@ fn test_part2_example3() {
             &"
 67,7,59,61"
                 .as_bytes()
-                .to_vec(),
-            700000,
-            754018
+                .to_vec()
         )
     );
 }
@@ -154,9 +110,7 @@ fn test_part2_example4() {
             &"
 67,x,7,59,61"
                 .as_bytes()
-                .to_vec(),
-            700000,
-            779210
+                .to_vec()
         )
     );
 }
@@ -169,9 +123,7 @@ fn test_part2_example5() {
             &"
 67,7,x,59,61"
                 .as_bytes()
-                .to_vec(),
-            1200000,
-            1261476
+                .to_vec()
         )
     );
 }
@@ -184,9 +136,7 @@ fn test_part2_example6() {
             &"
 1789,37,47,1889"
                 .as_bytes()
-                .to_vec(),
-            1200000000,
-            1202161486
+                .to_vec()
         )
     );
 }
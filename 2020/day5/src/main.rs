@@ -1,4 +1,4 @@
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read};
 use std::fs::File;
 use std::time::Instant;
 
@@ -16,7 +16,7 @@ fn part1(f: File) -> usize {
         .map(seat_to_id).max().unwrap()
 }
 
-fn part2(f: File) -> usize {
+fn part2<R: Read>(f: R) -> usize {
     let mut seats: [bool; 1024] = [false; 1024]; // 1111111111b+1
 
     BufReader::new(f)
@@ -33,7 +33,7 @@ fn part2(f: File) -> usize {
          .unwrap().0
 }
 
-fn _part2_slow(f: File) -> usize {
+fn _part2_slow<R: Read>(f: R) -> usize {
     let mut seats = BufReader::new(f)
                     .lines()
                     .map(|r| r.unwrap())
@@ -68,4 +68,49 @@ fn seat_to_id_works() {
     assert_eq!(seat_to_id("BFFFBBFRRR".to_owned()), 567);
     assert_eq!(seat_to_id("FFFBBBFRRR".to_owned()), 119);
     assert_eq!(seat_to_id("BBFFBBFRLL".to_owned()), 820);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+use aoc_util::{compare::{report, Alternatives}, fuzz::Rng};
+
+// Boarding passes of a full plane with rows at the front and back missing and one free seat.
+#[cfg(test)]
+fn generate_boarding_passes(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let first = 8 + rng.below(100);
+    let last = first + 10 + rng.below(1000 - first - 10);
+    let free = first + 1 + rng.below(last - first - 1);
+    let mut ids = (first..=last).filter(|&id| id != free).collect::<Vec<_>>();
+    for i in (1..ids.len()).rev() {
+        ids.swap(i, rng.below(i + 1));
+    }
+    ids.iter()
+       .map(|id| (0..10).rev().map(|bit| match (bit, id >> bit & 1) {
+           (3.., 1) => 'B',
+           (3.., _) => 'F',
+           (_, 1) => 'R',
+           _ => 'L'
+       }).collect::<String>())
+       .collect::<Vec<_>>()
+       .join("\n")
+}
+
+#[cfg(test)]
+fn part2_alternatives<'a>() -> Alternatives<'a, String, usize> {
+    Alternatives::new()
+        .add("array", |passes: &String| part2(passes.as_bytes()))
+        .add("slow", |passes: &String| _part2_slow(passes.as_bytes()))
+}
+
+#[test]
+fn test_alternatives_agree() {
+    let inputs = (0..100).map(generate_boarding_passes).collect::<Vec<_>>();
+    part2_alternatives().check_all(&inputs).unwrap();
+}
+
+// cargo test --release bench -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_alternatives() {
+    println!("part2\n{}", report(&part2_alternatives().bench(&generate_boarding_passes(0), 1000)));
+}
@@ -37,7 +37,9 @@ fn part1_fast(qd: &String) -> u32 {
             group
                 .bytes()
                 .filter(|&c| c != b'\n')
-                .fold(u32::MIN, |answers, answer| answers | 1_u32 << answer)
+                .fold(u32::MIN, |answers, answer| {
+                    answers | 1_u32 << (answer - b'a')
+                })
                 .count_ones()
         })
         .sum()
@@ -49,8 +51,9 @@ fn part2_fast(qd: &String) -> u32 {
             group
                 .lines()
                 .map(|l| {
-                    l.bytes()
-                        .fold(u32::MIN, |answers, answer| answers | 1_u32 << answer)
+                    l.bytes().fold(u32::MIN, |answers, answer| {
+                        answers | 1_u32 << (answer - b'a')
+                    })
                 })
                 .fold(u32::MAX, |answers, answer| answers & answer)
                 .count_ones()
@@ -79,3 +82,67 @@ fn main() {
 
     println!("Time: {}µs", s1.elapsed().as_micros());
 }
+
+#[cfg(test)]
+use aoc_util::{
+    compare::{report, Alternatives},
+    fuzz::Rng,
+};
+
+// Groups of people separated by blank lines, each with a few distinct answers a-z.
+#[cfg(test)]
+fn generate_questionnaire(groups: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    (0..groups)
+        .map(|_| {
+            (0..=rng.below(5))
+                .map(|_| {
+                    let mut answers = (b'a'..=b'z').collect::<Vec<_>>();
+                    (0..=rng.below(26))
+                        .map(|_| answers.swap_remove(rng.below(answers.len())) as char)
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+fn part1_alternatives<'a>() -> Alternatives<'a, String, usize> {
+    Alternatives::new()
+        .add("slow", _part1_slow)
+        .add("fast", |qd| part1_fast(qd) as usize)
+}
+
+#[cfg(test)]
+fn part2_alternatives<'a>() -> Alternatives<'a, String, usize> {
+    Alternatives::new()
+        .add("slow", _part2_slow)
+        .add("fast", |qd| part2_fast(qd) as usize)
+}
+
+#[test]
+fn test_alternatives_agree() {
+    let inputs = (0..200)
+        .map(|n| generate_questionnaire(n % 10 + 1, n as u64))
+        .collect::<Vec<_>>();
+    part1_alternatives().check_all(&inputs).unwrap();
+    part2_alternatives().check_all(&inputs).unwrap();
+}
+
+// cargo test --release bench -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_alternatives() {
+    let input = generate_questionnaire(500, 0);
+    println!(
+        "part1\n{}",
+        report(&part1_alternatives().bench(&input, 1000))
+    );
+    println!(
+        "part2\n{}",
+        report(&part2_alternatives().bench(&input, 1000))
+    );
+}
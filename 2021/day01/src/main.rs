@@ -142,3 +142,62 @@ fn test_part2_math_example() {
     assert_eq!(0, part2_math(&[].to_vec()));
     assert_eq!(5, part2_math(&_EXAMPLE.to_vec()));
 }
+
+#[cfg(test)]
+use aoc_util::{
+    compare::{report, Alternatives},
+    fuzz::Rng,
+};
+
+#[cfg(test)]
+fn generate_measurements(count: usize, seed: u64) -> Vec<usize> {
+    let mut rng = Rng::new(seed);
+    let mut depth = 100;
+    (0..count)
+        .map(|_| {
+            depth = (depth + rng.below(20)).saturating_sub(9);
+            depth
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn part1_alternatives<'a>() -> Alternatives<'a, Vec<usize>, usize> {
+    Alternatives::new()
+        .add("filter", _part1_filter)
+        .add("slow zip", _part1_slow_zip)
+        .add("windows", _part1_windows)
+        .add("math", part1_math)
+}
+
+#[cfg(test)]
+fn part2_alternatives<'a>() -> Alternatives<'a, Vec<usize>, usize> {
+    Alternatives::new()
+        .add("zip", _part2_zip)
+        .add("windows", _part2_windows)
+        .add("math", part2_math)
+}
+
+#[test]
+fn test_alternatives_agree() {
+    let inputs = (0..200)
+        .map(|n| generate_measurements(n % 20, n as u64))
+        .collect::<Vec<_>>();
+    part1_alternatives().check_all(&inputs).unwrap();
+    part2_alternatives().check_all(&inputs).unwrap();
+}
+
+// cargo test --release bench -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_alternatives() {
+    let input = generate_measurements(2000, 0);
+    println!(
+        "part1\n{}",
+        report(&part1_alternatives().bench(&input, 1000))
+    );
+    println!(
+        "part2\n{}",
+        report(&part2_alternatives().bench(&input, 1000))
+    );
+}
@@ -71,6 +71,11 @@ mod tests {
             45000
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
@@ -78,6 +78,11 @@ mod tests {
             70
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
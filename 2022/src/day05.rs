@@ -53,26 +53,23 @@ impl AoCDay<Input, Output> for Day {
                 }
             } else {
                 let elements = line.split_whitespace().collect::<Vec<_>>();
+                let stack_index = |position: usize| {
+                    elements
+                        .get(position)
+                        .and_then(|e| e.as_bytes().first())
+                        .and_then(|b| b.checked_sub(b'1'))
+                        .map(usize::from)
+                        .filter(|&i| i < stacks.len())
+                        .ok_or("invalid move: no such stack")
+                };
                 moves.push((
                     // move 27 from 2 to 1
                     elements
                         .get(1)
                         .ok_or("invalid move: no count found")?
                         .parse()?,
-                    (elements
-                        .get(3)
-                        .ok_or("invalid move: no from found")?
-                        .as_bytes()
-                        .first()
-                        .ok_or("invalid move: empty from")?
-                        - b'1') as usize,
-                    (elements
-                        .get(5)
-                        .ok_or("invalid move: no to found")?
-                        .as_bytes()
-                        .first()
-                        .ok_or("invalid move: empty to")?
-                        - b'1') as usize,
+                    stack_index(3)?,
+                    stack_index(5)?,
                 ));
             }
         }
@@ -137,6 +134,11 @@ mod tests {
             "MCD"
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
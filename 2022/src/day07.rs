@@ -19,9 +19,8 @@ impl AoCDay<Input, Output> for Day {
         for line in reader.lines() {
             let line = line?;
 
-            let command = &line[2..4];
-            match command {
-                "cd" => match &line[5..] {
+            match line.get(2..4) {
+                Some("cd") => match line.get(5..).ok_or("bad input: cd without directory")? {
                     "/" => {
                         current_path.clear();
                     }
@@ -32,9 +31,9 @@ impl AoCDay<Input, Output> for Day {
                         current_path.push(subdir.to_string());
                     }
                 },
-                "ls" => {}
+                Some("ls") => {}
                 _ => {
-                    if line[0..3] != *"dir" {
+                    if !line.starts_with("dir") {
                         let (size_str, filename) =
                             line.split_once(' ').ok_or("bad input: bad ls")?;
                         let mut path = current_path.clone();
@@ -107,6 +106,11 @@ mod tests {
             24933642
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
@@ -17,7 +17,9 @@ impl AoCDay<Input, Output> for Day {
                 let line = line?;
                 Ok((
                     *line.as_bytes().first().ok_or("bad input: empty line")?,
-                    line[2..].parse::<usize>()?,
+                    line.get(2..)
+                        .ok_or("bad input: no step count")?
+                        .parse::<usize>()?,
                 ))
             })
             .collect()
@@ -120,6 +122,11 @@ mod tests {
             36
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
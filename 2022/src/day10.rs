@@ -15,8 +15,12 @@ impl AoCDay<Input, Output> for Day {
             .lines()
             .map(|line| {
                 let line = line?;
-                if &line[..4] == "addx" {
-                    Ok(Some(line[5..].parse::<i16>()?))
+                if line.starts_with("addx") {
+                    Ok(Some(
+                        line.get(5..)
+                            .ok_or("bad input: addx without value")?
+                            .parse::<i16>()?,
+                    ))
                 } else {
                     Ok(None)
                 }
@@ -105,6 +109,11 @@ mod tests {
             42
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
@@ -1,6 +1,17 @@
 mod util;
 
-use std::{error::Error, io::BufRead};
+use aoc_util::number_theory::lcm_all;
+use aoc_util::parse::{integer, parse_blocks};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{digit1, line_ending, one_of},
+    combinator::map,
+    multi::separated_list1,
+    sequence::{delimited, preceded, tuple},
+    IResult,
+};
+use std::{error::Error, io};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day11";
@@ -14,55 +25,61 @@ struct Monkey {
     test: Box<dyn Fn(usize) -> usize>,
 }
 
+fn parse_monkey(i: &str) -> IResult<&str, Monkey> {
+    let (i, _) = tuple((tag("Monkey "), digit1, tag(":"), line_ending))(i)?;
+    let (i, initial_items) = delimited(
+        tag("  Starting items: "),
+        separated_list1(tag(", "), integer),
+        line_ending,
+    )(i)?;
+    let (i, (_, operator, _, operand, _)) = tuple((
+        tag("  Operation: new = old "),
+        one_of("*+"),
+        tag(" "),
+        alt((map(tag("old"), |_| None), map(integer::<usize>, Some))),
+        line_ending,
+    ))(i)?;
+    let (i, test_divisible_by) =
+        delimited(tag("  Test: divisible by "), integer::<usize>, line_ending)(i)?;
+    let (i, test_target_true) = delimited(
+        tag("    If true: throw to monkey "),
+        integer::<usize>,
+        line_ending,
+    )(i)?;
+    let (i, test_target_false) =
+        preceded(tag("    If false: throw to monkey "), integer::<usize>)(i)?;
+
+    let op: Box<dyn Fn(usize) -> usize> = match (operator, operand) {
+        ('*', None) => Box::new(|x| x * x),
+        ('*', Some(int)) => Box::new(move |x| x * int),
+        (_, None) => Box::new(|x| x + x),
+        (_, Some(int)) => Box::new(move |x| x + int),
+    };
+    let test = Box::new(move |x| {
+        if x % test_divisible_by == 0 {
+            test_target_true
+        } else {
+            test_target_false
+        }
+    });
+
+    Ok((
+        i,
+        Monkey {
+            initial_items,
+            div: test_divisible_by,
+            op,
+            test,
+        },
+    ))
+}
+
 struct Day {}
 
 impl AoCDay<Input, Output> for Day {
     fn parse_input(&self, id: &str) -> Result<Input, Box<dyn Error>> {
-        get_reader(id)?
-            .lines()
-            .collect::<Result<Vec<_>, _>>()?
-            .chunks(7)
-            .map(|monkey_lines| {
-                let initial_items = monkey_lines[1][18..]
-                    .split(", ")
-                    .map(|x| x.parse())
-                    .collect::<Result<_, _>>()?;
-
-                let op_line = &monkey_lines[2];
-                let op: Result<Box<dyn Fn(_) -> _>, Box<dyn Error>> =
-                    match (&op_line[23..24], &op_line[25..]) {
-                        ("*", "old") => Ok(Box::new(|x| x * x)),
-                        ("*", int) => {
-                            let int = int.parse::<usize>()?;
-                            Ok(Box::new(move |x| x * int))
-                        }
-                        ("+", "old") => Ok(Box::new(|x| x + x)),
-                        ("+", int) => {
-                            let int = int.parse::<usize>()?;
-                            Ok(Box::new(move |x| x + int))
-                        }
-                        _ => Err("Invalid op".into()),
-                    };
-
-                let test_divisible_by = monkey_lines[3][21..].parse()?;
-                let test_target_true = monkey_lines[4][29..].parse()?;
-                let test_target_false = monkey_lines[5][30..].parse()?;
-                let test = Box::new(move |x| {
-                    if x % test_divisible_by == 0 {
-                        test_target_true
-                    } else {
-                        test_target_false
-                    }
-                });
-
-                Ok(Monkey {
-                    initial_items,
-                    div: test_divisible_by,
-                    op: op?,
-                    test,
-                })
-            })
-            .collect()
+        let input = io::read_to_string(get_reader(id)?)?;
+        Ok(parse_blocks(&input, parse_monkey)?)
     }
 
     fn part1(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
@@ -95,7 +112,7 @@ impl AoCDay<Input, Output> for Day {
 
         // Basically: to keep it in a ring where it is still divisible
         // by all monkey-dividers, we need modulo it using their lcm
-        let ring = input.iter().map(|m| m.div).product::<usize>();
+        let ring = lcm_all(input.iter().map(|m| m.div));
 
         let mut monkey_inspections = vec![0; input.len()];
         for _ in 0..10000 {
@@ -139,6 +156,11 @@ mod tests {
             2713310158
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
@@ -1,6 +1,6 @@
 mod util;
 
-use pathfinding::prelude::dijkstra;
+use aoc_util::graph::shortest_paths_bucketed;
 use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
@@ -135,27 +135,17 @@ impl AoCDay<Input, Output> for Day {
     }
 
     fn part1(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
-        if let Some((_, cost)) = dijkstra(
-            &input.end,
-            |idx| input.get_available_exits(*idx),
-            |&idx| idx == input.start,
-        ) {
-            Ok(cost)
-        } else {
-            Err("No path found".into())
-        }
+        shortest_paths_bucketed([input.end], |idx| input.get_available_exits(*idx))
+            .closest(|&idx| idx == input.start)
+            .map(|(_, cost)| cost)
+            .ok_or("No path found".into())
     }
 
     fn part2(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
-        if let Some((_, cost)) = dijkstra(
-            &input.end,
-            |idx| input.get_available_exits(*idx),
-            |&idx| input.tiles[idx] == b'a',
-        ) {
-            Ok(cost)
-        } else {
-            Err("No path found".into())
-        }
+        shortest_paths_bucketed([input.end], |idx| input.get_available_exits(*idx))
+            .closest(|&idx| input.tiles[idx] == b'a')
+            .map(|(_, cost)| cost)
+            .ok_or("No path found".into())
     }
 }
 
@@ -182,6 +172,11 @@ mod tests {
             29
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
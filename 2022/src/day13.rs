@@ -67,10 +67,11 @@ impl AoCDay<Input, Output> for Day {
                     Ok((_, o)) => o,
                     Err(e) => Err(format!("Parse error: {:?}", e))?,
                 };
-                let right = match parse_list_or_int(&pairs[1]) {
-                    Ok((_, o)) => o,
-                    Err(e) => Err(format!("Parse error: {:?}", e))?,
-                };
+                let right =
+                    match parse_list_or_int(pairs.get(1).ok_or("Parse error: No right packet")?) {
+                        Ok((_, o)) => o,
+                        Err(e) => Err(format!("Parse error: {:?}", e))?,
+                    };
                 Ok((left, right))
             })
             .collect()
@@ -129,6 +130,11 @@ mod tests {
             140
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
@@ -8,6 +8,9 @@ const ID: &str = "day14";
 type Input = Vec<HashSet<usize>>;
 type Output = usize;
 
+// Far more than any real cave, keeps bad input from filling the memory
+const MAX_COORDINATE: usize = 100_000;
+
 struct Day {}
 
 impl AoCDay<Input, Output> for Day {
@@ -27,6 +30,16 @@ impl AoCDay<Input, Output> for Day {
                 let from = pairs[0];
                 let to = pairs[1];
 
+                if from.0 != to.0 && from.1 != to.1 {
+                    Err("Bad input: Diagonal line")?;
+                }
+                if [from.0, from.1, to.0, to.1]
+                    .iter()
+                    .any(|&c| c > MAX_COORDINATE)
+                {
+                    Err("Bad input: Coordinate too large")?;
+                }
+
                 let (from_x, to_x) = if from.0 <= to.0 {
                     (from.0, to.0)
                 } else {
@@ -156,6 +169,11 @@ mod tests {
             93
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
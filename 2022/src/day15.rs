@@ -1,5 +1,6 @@
 mod util;
 
+use aoc_util::interval::IntervalSet;
 use nom::bytes::complete as nom_bytes;
 use nom::character::complete as nom_char;
 use nom::{sequence::tuple, IResult};
@@ -86,7 +87,7 @@ impl AoCDay<Input, Output> for Day {
         const MAX_X_Y_RANGE: std::ops::RangeInclusive<i32> = 0..=MAX_X_Y;
 
         // Note: [_; MAX_X_Y as usize + 1] would blow the stack
-        let mut no_beacon_ranges_per_y = vec![Vec::<(i32, i32)>::new(); MAX_X_Y as usize + 1];
+        let mut no_beacon_ranges_per_y = vec![IntervalSet::<i32>::new(); MAX_X_Y as usize + 1];
 
         for (sensor, beacon) in input {
             let y_distance = (sensor.1 - beacon.1).abs();
@@ -103,55 +104,20 @@ impl AoCDay<Input, Output> for Day {
                 let x_min = (sensor.0 - x_max_distance).max(0);
                 let x_max = (sensor.0 + x_max_distance).min(MAX_X_Y);
 
-                insert_range(&mut no_beacon_ranges_per_y[y as usize], (x_min, x_max));
+                no_beacon_ranges_per_y[y as usize].insert_inclusive(x_min..=x_max)?;
             }
         }
 
         no_beacon_ranges_per_y
             .par_iter()
             .enumerate()
-            .find_any(|(_, ranges)| ranges.len() == 2 && ranges[1].0 - ranges[0].1 == 2)
-            .map(|(y, ranges)| (ranges[0].1 + 1) as i64 * 4000000 + y as i64)
+            .map(|(y, ranges)| (y, ranges.ranges()))
+            .find_any(|(_, ranges)| ranges.len() == 2 && ranges[1].start - ranges[0].end == 1)
+            .map(|(y, ranges)| ranges[0].end as i64 * 4000000 + y as i64)
             .ok_or("no solution found".into())
     }
 }
 
-fn insert_range(ranges: &mut Vec<(i32, i32)>, range: (i32, i32)) {
-    let mut i = 0;
-    while i < ranges.len() {
-        let (start, end) = ranges[i];
-        if range.0 > end {
-            i += 1;
-            continue;
-        }
-        if range.1 < start {
-            ranges.insert(i, range);
-            return;
-        }
-        if range.0 < start {
-            ranges[i].0 = range.0;
-        }
-        if range.1 > end {
-            ranges[i].1 = range.1;
-        }
-        let j = i + 1;
-        while j < ranges.len() {
-            let (start, end) = ranges[j];
-            if range.1 < start {
-                return;
-            }
-            if range.1 < end {
-                ranges[i].1 = end;
-                ranges.remove(j);
-                return;
-            }
-            ranges.remove(j);
-        }
-        return;
-    }
-    ranges.push(range);
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,6 +141,11 @@ mod tests {
             56000011
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
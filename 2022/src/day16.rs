@@ -1,12 +1,12 @@
 mod util;
 
+use aoc_util::graph::bfs_distances;
 use nom::branch::alt;
 use nom::bytes::complete as nom_bytes;
 use nom::character::complete as nom_char;
 use nom::multi::separated_list1;
 use nom::sequence::pair;
 use nom::{sequence::tuple, IResult};
-use pathfinding::prelude::bfs;
 
 use std::io::BufRead;
 use std::{collections::HashMap, error::Error};
@@ -78,6 +78,7 @@ fn drop_zero_flow_valves(input: &Input) -> InputWithCost {
         .iter()
         .filter(|&(&key, &(flow_rate, _))| key == ('A', 'A') || flow_rate > 0)
         .map(|(key, &(flow_rate, _))| {
+            let distances = bfs_distances(*key, |n| input.get(n).unwrap().1.clone());
             (
                 *key,
                 (
@@ -86,13 +87,9 @@ fn drop_zero_flow_valves(input: &Input) -> InputWithCost {
                         .iter()
                         .filter(|&(_, &(flow_rate, _))| flow_rate > 0)
                         .filter(|&(other_key, (_, _))| other_key != key)
-                        .filter_map(|(&other_key, (_, _))| {
-                            let path = bfs(
-                                key,
-                                |n| input.get(n).unwrap().1.clone(),
-                                |&n| n == other_key,
-                            );
-                            path.map(|path| (other_key, path.len() as u64 - 1))
+                        .filter_map(|(other_key, (_, _))| {
+                            let distance = distances.get(other_key)?;
+                            Some((*other_key, *distance as u64))
                         })
                         .collect::<Vec<_>>(),
                 ),
@@ -195,6 +192,11 @@ mod tests {
             1707
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
 
 fn main() {
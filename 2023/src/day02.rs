@@ -29,10 +29,10 @@ impl AoCDay<Input, Output> for Day {
 
         for gameline in reader.lines() {
             let gameline = gameline?;
-            let colon_split = gameline.split(": ").collect::<Vec<&str>>();
-            let game_id = &colon_split[0][5..].parse::<usize>()?;
+            let (game_str, rounds_str) = gameline.split_once(": ").ok_or("Invalid game")?;
+            let game_id = &game_str.get(5..).ok_or("Invalid game")?.parse::<usize>()?;
 
-            let rounds = colon_split[1].split("; ").collect::<Vec<&str>>();
+            let rounds = rounds_str.split("; ").collect::<Vec<&str>>();
 
             let mut game = Game {
                 id: *game_id,
@@ -47,11 +47,11 @@ impl AoCDay<Input, Output> for Day {
                     blue: 0,
                 };
                 for color in sampling_split {
-                    let color_split = color.split(' ').collect::<Vec<&str>>();
-                    match color_split[1] {
-                        "red" => sampling.red = color_split[0].parse::<usize>()?,
-                        "green" => sampling.green = color_split[0].parse::<usize>()?,
-                        "blue" => sampling.blue = color_split[0].parse::<usize>()?,
+                    let (count, name) = color.split_once(' ').ok_or("Invalid color")?;
+                    match name {
+                        "red" => sampling.red = count.parse::<usize>()?,
+                        "green" => sampling.green = count.parse::<usize>()?,
+                        "blue" => sampling.blue = count.parse::<usize>()?,
                         _ => Err("Invalid color")?,
                     }
                 }
@@ -124,4 +124,9 @@ mod tests {
             2286
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -210,4 +210,9 @@ mod tests {
         assert_eq!(get_star_position(&['a', '*']), Some(1));
         assert_eq!(get_star_position(&['a', '*', 'b']), Some(1));
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
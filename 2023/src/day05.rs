@@ -1,6 +1,6 @@
 mod util;
 
-use rayon::prelude::*;
+use aoc_util::interval::IntervalSet;
 use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
@@ -117,25 +117,32 @@ impl AoCDay<Input, Output> for Day {
     }
 
     fn part2(&self, almanac: &Input) -> Result<Output, Box<dyn Error>> {
-        let mut stupid_but_possible = Vec::new();
-
-        for ranges in almanac.seeds.chunks_exact(2) {
-            for i in ranges[0]..(ranges[0] + ranges[1]) {
-                stupid_but_possible.push(i);
-            }
-        }
-
-        Ok(stupid_but_possible
-            .par_iter()
-            .map(|s| source_map(*s, &almanac.seed_to_soil_map))
-            .map(|s| source_map(s, &almanac.soil_to_fertilizer_map))
-            .map(|s| source_map(s, &almanac.fertilizer_to_water_map))
-            .map(|s| source_map(s, &almanac.water_to_light_map))
-            .map(|s| source_map(s, &almanac.light_to_temperature_map))
-            .map(|s| source_map(s, &almanac.temperature_to_humidity_map))
-            .map(|s| source_map(s, &almanac.humidity_to_location_map))
-            .min()
-            .unwrap())
+        let seeds = almanac
+            .seeds
+            .chunks_exact(2)
+            .map(|ranges| ranges[0]..(ranges[0] + ranges[1]))
+            .collect::<IntervalSet<_>>();
+
+        [
+            &almanac.seed_to_soil_map,
+            &almanac.soil_to_fertilizer_map,
+            &almanac.fertilizer_to_water_map,
+            &almanac.water_to_light_map,
+            &almanac.light_to_temperature_map,
+            &almanac.temperature_to_humidity_map,
+            &almanac.humidity_to_location_map,
+        ]
+        .iter()
+        .fold(seeds, |ranges, mappers| {
+            ranges.map_piecewise(mappers.iter().map(|mapper| {
+                (
+                    mapper.source_start..mapper.source_start + mapper.length,
+                    mapper.destination_start,
+                )
+            }))
+        })
+        .min()
+        .ok_or("No seeds".into())
     }
 }
 
@@ -204,4 +211,9 @@ mod tests {
             46
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -14,13 +14,17 @@ impl AoCDay<Input, Output> for Day {
         let mut lines = get_reader(id)?.lines();
 
         let times_str = lines.next().ok_or("Parse Error")??;
-        let times = &times_str[11..] // 'Time:      '
+        let times = times_str
+            .get(11..)
+            .ok_or("Parse Error")? // 'Time:      '
             .split_whitespace()
             .map(|t| t.parse::<usize>())
             .collect::<Result<Vec<_>, _>>()?;
 
         let distances_str = lines.next().ok_or("Parse Error")??;
-        let distances = &distances_str[11..] // 'Distance:      '
+        let distances = distances_str
+            .get(11..)
+            .ok_or("Parse Error")? // 'Distance:      '
             .split_whitespace()
             .map(|t| t.parse::<usize>())
             .collect::<Result<Vec<_>, _>>()?;
@@ -94,4 +98,9 @@ mod tests {
             71503
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
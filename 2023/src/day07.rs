@@ -361,4 +361,9 @@ mod tests {
         hand.prepare_mode();
         assert!(hand.level == 0);
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -1,5 +1,6 @@
 mod util;
 
+use aoc_util::number_theory::lcm_all;
 use std::{collections::HashMap, error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
@@ -127,19 +128,7 @@ impl AoCDay<Input, Output> for Day {
             })
             .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
 
-        Ok(steps.iter().fold(1, |a, b| lcm(a, *b)))
-    }
-}
-
-fn lcm(a: usize, b: usize) -> usize {
-    (a * b) / gcd(a, b)
-}
-
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
+        Ok(lcm_all(steps.iter().copied()))
     }
 }
 
@@ -170,4 +159,9 @@ mod tests {
             6
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
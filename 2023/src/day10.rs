@@ -1,6 +1,7 @@
 mod util;
 
-use std::{collections::HashSet, error::Error, io::BufRead};
+use aoc_util::{geometry::Point2, polygon::Polygon};
+use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day10";
@@ -14,32 +15,30 @@ const NORTH_WEST_PIPE: char = 'J';
 const SOUTH_WEST_PIPE: char = '7';
 const SOUTH_EAST_PIPE: char = 'F';
 const START: char = 'S';
-const GROUND: char = '.';
-const WALL: char = '#';
 
 struct Day {}
 
 impl AoCDay<Input, Output> for Day {
     fn parse_input(&self, id: &str) -> Result<Input, Box<dyn Error>> {
-        let (start_y, start_x, map) = get_reader(id)?.lines().enumerate().try_fold(
-            (0, 0, Vec::new()),
-            |(mut y, mut x, mut map), (y_index, line)| {
+        let (start, map) = get_reader(id)?.lines().enumerate().try_fold(
+            (None, Vec::new()),
+            |(mut start, mut map), (y_index, line)| {
                 let line = line?;
                 map.push(
                     line.chars()
                         .enumerate()
                         .inspect(|(x_index, c)| {
                             if *c == START {
-                                y = y_index;
-                                x = *x_index;
+                                start = Some((y_index, *x_index));
                             }
                         })
                         .map(|(_, c)| c)
                         .collect(),
                 );
-                Ok::<_, Box<dyn Error>>((y, x, map))
+                Ok::<_, Box<dyn Error>>((start, map))
             },
         )?;
+        let (start_y, start_x) = start.ok_or("no start")?;
         let mut map = map;
 
         let start_pipe = get_start_pipe(&start_y, &start_x, &map).ok_or("no valid start pipe")?;
@@ -54,111 +53,23 @@ impl AoCDay<Input, Output> for Day {
 
     fn part2(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
         let loop_pipe_positions = get_loop_pipe_positions(input)?;
-        let map = &input.2;
-
-        let mut exploded_map = explode_map(map, &loop_pipe_positions).ok_or("no valid map")?;
-
-        let mut queue = vec![(0, 0)];
-        while let Some((y, x)) = queue.pop() {
-            for (y, x) in [(-1, 0), (0, 1), (1, 0), (0, -1)]
-                .iter()
-                .map(|(dy, dx)| (y as isize + dy, x as isize + dx))
-            {
-                let y = y as usize;
-                let x = x as usize;
-                if exploded_map
-                    .get(y)
-                    .and_then(|column| column.get(x))
-                    .filter(|c| **c == GROUND)
-                    .is_some()
-                {
-                    exploded_map[y][x] = WALL;
-                    queue.push((y, x));
-                }
-            }
-        }
-
-        let result = exploded_map
-            .iter()
-            .enumerate()
-            .flat_map(|(y, column)| {
-                column
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, c)| **c == GROUND)
-                    .map(move |(x, _)| (y, x))
-            })
-            .map(|(y, x)| (y / 3, x / 3))
-            .filter(|(y, x)| !loop_pipe_positions.contains(&(*y as isize, *x as isize)))
-            .collect::<HashSet<_>>();
-
-        Ok(result.len())
-    }
-}
-
-fn explode_map(
-    map: &[Vec<char>],
-    loop_pipe_positions: &HashSet<(isize, isize)>,
-) -> Option<Vec<Vec<char>>> {
-    let mut exploded_map = vec![vec![GROUND; map[0].len() * 3]; map.len() * 3];
-    for (y, column) in map.iter().enumerate() {
-        for (x, c) in column
-            .iter()
-            .enumerate()
-            .filter(|(_, c)| **c != GROUND)
-            .filter(|(x, _)| loop_pipe_positions.contains(&(y as isize, *x as isize)))
-        {
-            let exploded_char = explode_char(*c)?;
-            exploded_map[y * 3..y * 3 + 3]
-                .iter_mut()
-                .flat_map(|column| column.iter_mut().skip(x * 3).take(3))
-                .zip(exploded_char.iter().flat_map(|column| column.iter()))
-                .for_each(|(c1, c2)| *c1 = *c2);
-        }
-    }
-    Some(exploded_map)
-}
+        let pipe_loop = Polygon::new(
+            loop_pipe_positions
+                .into_iter()
+                .map(Point2::from_yx)
+                .collect(),
+        );
 
-fn explode_char(c: char) -> Option<[[char; 3]; 3]> {
-    match c {
-        VERTICAL_PIPE => Some([
-            [GROUND, WALL, GROUND],
-            [GROUND, WALL, GROUND],
-            [GROUND, WALL, GROUND],
-        ]),
-        HORIZONTAL_PIPE => Some([
-            [GROUND, GROUND, GROUND],
-            [WALL, WALL, WALL],
-            [GROUND, GROUND, GROUND],
-        ]),
-        NORTH_EAST_PIPE => Some([
-            [GROUND, WALL, GROUND],
-            [GROUND, WALL, WALL],
-            [GROUND, GROUND, GROUND],
-        ]),
-        NORTH_WEST_PIPE => Some([
-            [GROUND, WALL, GROUND],
-            [WALL, WALL, GROUND],
-            [GROUND, GROUND, GROUND],
-        ]),
-        SOUTH_EAST_PIPE => Some([
-            [GROUND, GROUND, GROUND],
-            [GROUND, WALL, WALL],
-            [GROUND, WALL, GROUND],
-        ]),
-        SOUTH_WEST_PIPE => Some([
-            [GROUND, GROUND, GROUND],
-            [WALL, WALL, GROUND],
-            [GROUND, WALL, GROUND],
-        ]),
-        _ => None,
+        // Every loop tile is a boundary point, the enclosed tiles are the interior points
+        Ok(pipe_loop.interior_points())
     }
 }
 
 // Unsafe: This is under the assumption that the start is actually part of a loop
+// The positions are in loop order
 fn get_loop_pipe_positions(
     (start_y, start_x, map): &Input,
-) -> Result<HashSet<(isize, isize)>, Box<dyn Error>> {
+) -> Result<Vec<(isize, isize)>, Box<dyn Error>> {
     let start_pipe = map
         .get(*start_y)
         .and_then(|column| column.get(*start_x))
@@ -172,11 +83,11 @@ fn get_loop_pipe_positions(
         *start_x as isize + initial_directions.0 .1,
     );
 
-    let mut positions = HashSet::new();
-    positions.insert(previous_position);
-    positions.insert(current_position);
+    let mut positions = vec![previous_position];
 
     while current_position != (*start_y as isize, *start_x as isize) {
+        positions.push(current_position);
+
         let pipe = map
             .get(current_position.0 as usize)
             .and_then(|column| column.get(current_position.1 as usize))
@@ -200,8 +111,6 @@ fn get_loop_pipe_positions(
 
         previous_position = current_position;
         current_position = new_position;
-
-        positions.insert(current_position);
     }
 
     Ok(positions)
@@ -221,18 +130,10 @@ fn get_next_directions(pipe: char) -> Option<((isize, isize), (isize, isize))> {
 
 // Unsafe: This is under the assumption that the start pipe is biunique
 fn get_start_pipe(start_y: &usize, start_x: &usize, map: &[Vec<char>]) -> Option<char> {
-    let start_pipe_north = if start_y > &0 {
-        Some(&map[*start_y - 1][*start_x])
-    } else {
-        None
-    };
+    let start_pipe_north = start_y.checked_sub(1).and_then(|y| map[y].get(*start_x));
     let start_pipe_east = map[*start_y].get(*start_x + 1);
-    let start_pipe_south = map.get(*start_y + 1).map(|column| &column[*start_x]);
-    let start_pipe_west = if start_x > &0 {
-        Some(&map[*start_y][*start_x - 1])
-    } else {
-        None
-    };
+    let start_pipe_south = map.get(*start_y + 1).and_then(|row| row.get(*start_x));
+    let start_pipe_west = start_x.checked_sub(1).and_then(|x| map[*start_y].get(x));
 
     match (
         start_pipe_north
@@ -332,4 +233,9 @@ mod tests {
             10
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -120,4 +120,9 @@ mod tests {
         assert_eq!(calulate_distances(&input, 10).unwrap(), 1030);
         assert_eq!(calulate_distances(&input, 100).unwrap(), 8410);
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
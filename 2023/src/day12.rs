@@ -1,7 +1,8 @@
 mod util;
 
+use aoc_util::memo::Memo;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use std::{collections::HashMap, error::Error, io::BufRead};
+use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day12";
@@ -84,12 +85,12 @@ fn calulate_arrangements_rec(
 ) -> usize {
     // Only one cache per spring states, since it doesn't now about "operational_allowed" and
     // it is too expensive to copy it in every iterator anyway
-    let mut cache = HashMap::new();
+    let mut cache = Memo::new();
     calulate_arrangements_rec_internal(&mut cache, spring_states, broken_group_sizes, true)
 }
 
 fn calulate_arrangements_rec_internal(
-    cache: &mut HashMap<(Vec<Option<State>>, Vec<usize>), usize>,
+    cache: &mut Memo<(Vec<Option<State>>, Vec<usize>), usize>,
     spring_states: &Vec<Option<State>>,
     broken_group_sizes: &Vec<usize>,
     operational_allowed: bool,
@@ -98,20 +99,25 @@ fn calulate_arrangements_rec_internal(
         return 1;
     }
 
-    if let Some(&arrangements) =
-        cache.get(&(spring_states.to_owned(), broken_group_sizes.to_owned()))
-    {
-        return arrangements;
-    }
-
-    let mut arrangements = 0;
+    let key = (spring_states.to_owned(), broken_group_sizes.to_owned());
+    cache.get_or_compute(key, |cache| {
+        let mut arrangements = 0;
 
-    if let Some(current_element) = spring_states.first() {
-        match current_element {
-            None => {
-                if operational_allowed {
+        if let Some(current_element) = spring_states.first() {
+            match current_element {
+                None => {
+                    if operational_allowed {
+                        let mut permutation = spring_states.clone();
+                        permutation[0] = Some(State::Operational);
+                        arrangements += calulate_arrangements_rec_internal(
+                            cache,
+                            &permutation,
+                            broken_group_sizes,
+                            true,
+                        );
+                    }
                     let mut permutation = spring_states.clone();
-                    permutation[0] = Some(State::Operational);
+                    permutation[0] = Some(State::Broken);
                     arrangements += calulate_arrangements_rec_internal(
                         cache,
                         &permutation,
@@ -119,63 +125,51 @@ fn calulate_arrangements_rec_internal(
                         true,
                     );
                 }
-                let mut permutation = spring_states.clone();
-                permutation[0] = Some(State::Broken);
-                arrangements += calulate_arrangements_rec_internal(
-                    cache,
-                    &permutation,
-                    broken_group_sizes,
-                    true,
-                );
-            }
-            Some(State::Operational) => {
-                if operational_allowed {
-                    let spring_states = spring_states.iter().skip(1).cloned().collect::<Vec<_>>();
-                    arrangements += calulate_arrangements_rec_internal(
-                        cache,
-                        &spring_states,
-                        broken_group_sizes,
-                        true,
-                    );
+                Some(State::Operational) => {
+                    if operational_allowed {
+                        let spring_states =
+                            spring_states.iter().skip(1).cloned().collect::<Vec<_>>();
+                        arrangements += calulate_arrangements_rec_internal(
+                            cache,
+                            &spring_states,
+                            broken_group_sizes,
+                            true,
+                        );
+                    }
                 }
-            }
-            Some(State::Broken) => {
-                let mut broken_group_sizes = broken_group_sizes.to_owned();
-                if let Some(expected_group_size) = broken_group_sizes.get_mut(0) {
-                    if expected_group_size == &1 {
-                        if spring_states.get(1) != Some(&Some(State::Broken)) {
+                Some(State::Broken) => {
+                    let mut broken_group_sizes = broken_group_sizes.to_owned();
+                    if let Some(expected_group_size) = broken_group_sizes.get_mut(0) {
+                        if expected_group_size == &1 {
+                            if spring_states.get(1) != Some(&Some(State::Broken)) {
+                                let spring_states =
+                                    spring_states.iter().skip(2).cloned().collect::<Vec<_>>();
+                                broken_group_sizes.remove(0);
+                                arrangements += calulate_arrangements_rec_internal(
+                                    cache,
+                                    &spring_states,
+                                    &broken_group_sizes,
+                                    true,
+                                );
+                            }
+                        } else if *expected_group_size > 1 {
+                            *expected_group_size -= 1;
                             let spring_states =
-                                spring_states.iter().skip(2).cloned().collect::<Vec<_>>();
-                            broken_group_sizes.remove(0);
+                                spring_states.iter().skip(1).cloned().collect::<Vec<_>>();
                             arrangements += calulate_arrangements_rec_internal(
                                 cache,
                                 &spring_states,
                                 &broken_group_sizes,
-                                true,
+                                false, // [Some(Broken), None, None, None, None] [2, 1] is 2, not 3
                             );
                         }
-                    } else if *expected_group_size > 1 {
-                        *expected_group_size -= 1;
-                        let spring_states =
-                            spring_states.iter().skip(1).cloned().collect::<Vec<_>>();
-                        arrangements += calulate_arrangements_rec_internal(
-                            cache,
-                            &spring_states,
-                            &broken_group_sizes,
-                            false, // [Some(Broken), None, None, None, None] [2, 1] is 2, not 3
-                        );
                     }
                 }
             }
         }
-    }
-
-    cache.insert(
-        (spring_states.to_owned(), broken_group_sizes.to_owned()),
-        arrangements,
-    );
 
-    arrangements
+        arrangements
+    })
 }
 
 fn main() {
@@ -494,4 +488,9 @@ mod tests {
         );
         assert_eq!(calulate_arrangements_rec(spring_states, groups), 2,);
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -1,6 +1,6 @@
 mod util;
 
-use pathfinding::directed::cycle_detection::floyd;
+use aoc_util::cycle::state_at;
 use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
@@ -39,17 +39,11 @@ impl AoCDay<Input, Output> for Day {
     }
 
     fn part2(&self, map: &Input) -> Result<Output, Box<dyn Error>> {
-        let (cycle_size, map, index_of_first_element) = floyd(map.clone(), cycle);
-
-        let remaining_cycles = (1000000000 - index_of_first_element) % cycle_size;
-
-        Ok(calculate_load(
-            &(0..remaining_cycles).fold(map.clone(), |map, _| cycle(map)),
-        ))
+        Ok(calculate_load(&state_at(map.clone(), cycle, 1000000000)))
     }
 }
 
-fn cycle(map: Input) -> Input {
+fn cycle(map: &Input) -> Input {
     let mut map = map.clone();
 
     map = tilt_north(&mut map).to_vec();
@@ -174,4 +168,9 @@ mod tests {
             64
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
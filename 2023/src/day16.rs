@@ -1,7 +1,11 @@
 mod util;
 
+use aoc_util::{
+    bitset::StateBitSet,
+    geometry::{Direction4, Point2},
+};
 use rayon::iter::{ParallelBridge, ParallelIterator};
-use std::{collections::HashSet, error::Error, io::BufRead};
+use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day16";
@@ -19,18 +23,10 @@ enum Tile {
 
 #[derive(Eq, Hash, PartialEq, Copy, Clone, Debug)]
 struct Beam {
-    direction: BeamDirection,
+    direction: Direction4,
     position: (isize, isize),
 }
 
-#[derive(Eq, Hash, PartialEq, Copy, Clone, Debug)]
-enum BeamDirection {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 struct Day {}
 
 impl AoCDay<Input, Output> for Day {
@@ -56,17 +52,13 @@ impl AoCDay<Input, Output> for Day {
     }
 
     fn part1(&self, map: &Input) -> Result<Output, Box<dyn Error>> {
-        let mut movements = HashSet::new();
+        let mut movements = StateBitSet::new(map.first().map_or(0, |r| r.len()), map.len());
         let beam = Beam {
-            direction: BeamDirection::Right,
+            direction: Direction4::East,
             position: (0, 0),
         };
         traverse(beam, map, &mut movements);
-        Ok(movements
-            .iter()
-            .map(|b| b.position)
-            .collect::<HashSet<_>>()
-            .len())
+        Ok(movements.positions().len())
     }
 
     fn part2(&self, map: &Input) -> Result<Output, Box<dyn Error>> {
@@ -78,38 +70,34 @@ impl AoCDay<Input, Output> for Day {
             .flat_map(|(y, x)| {
                 [
                     Beam {
-                        direction: BeamDirection::Down,
+                        direction: Direction4::South,
                         position: (0, x as isize),
                     },
                     Beam {
-                        direction: BeamDirection::Up,
+                        direction: Direction4::North,
                         position: (max_y as isize, x as isize),
                     },
                     Beam {
-                        direction: BeamDirection::Right,
+                        direction: Direction4::East,
                         position: (y as isize, 0),
                     },
                     Beam {
-                        direction: BeamDirection::Left,
+                        direction: Direction4::West,
                         position: (y as isize, max_x as isize),
                     },
                 ]
             })
             .map(|b| {
-                let mut movements = HashSet::new();
+                let mut movements = StateBitSet::new(max_x, max_y);
                 traverse(b, map, &mut movements);
-                movements
-                    .iter()
-                    .map(|b| b.position)
-                    .collect::<HashSet<_>>()
-                    .len()
+                movements.positions().len()
             })
             .max()
             .unwrap_or(0))
     }
 }
 
-fn traverse(beam: Beam, map: &Input, movements: &mut HashSet<Beam>) {
+fn traverse(beam: Beam, map: &Input, movements: &mut StateBitSet) {
     if beam.position.0 < 0
         || beam.position.0 >= map.len() as isize
         || beam.position.1 < 0
@@ -118,41 +106,42 @@ fn traverse(beam: Beam, map: &Input, movements: &mut HashSet<Beam>) {
         return;
     }
 
-    if !movements.insert(beam) {
+    let position = Point2::new(beam.position.1 as usize, beam.position.0 as usize);
+    if !movements.insert(position, beam.direction) {
         return;
     }
 
     let tile = &map[beam.position.0 as usize][beam.position.1 as usize];
 
     match (beam.direction, tile) {
-        (BeamDirection::Up, Tile::Empty) | (BeamDirection::Up, Tile::VerticalSplitter) => traverse(
+        (Direction4::North, Tile::Empty) | (Direction4::North, Tile::VerticalSplitter) => traverse(
             Beam {
-                direction: BeamDirection::Up,
+                direction: Direction4::North,
                 position: (beam.position.0 - 1, beam.position.1),
             },
             map,
             movements,
         ),
-        (BeamDirection::Up, Tile::RightAngleMirror) => traverse(
+        (Direction4::North, Tile::RightAngleMirror) => traverse(
             Beam {
-                direction: BeamDirection::Right,
+                direction: Direction4::East,
                 position: (beam.position.0, beam.position.1 + 1),
             },
             map,
             movements,
         ),
-        (BeamDirection::Up, Tile::LeftAngleMirror) => traverse(
+        (Direction4::North, Tile::LeftAngleMirror) => traverse(
             Beam {
-                direction: BeamDirection::Left,
+                direction: Direction4::West,
                 position: (beam.position.0, beam.position.1 - 1),
             },
             map,
             movements,
         ),
-        (BeamDirection::Up, Tile::HorizontalSplitter) => {
+        (Direction4::North, Tile::HorizontalSplitter) => {
             traverse(
                 Beam {
-                    direction: BeamDirection::Left,
+                    direction: Direction4::West,
                     position: (beam.position.0, beam.position.1 - 1),
                 },
                 map,
@@ -160,43 +149,41 @@ fn traverse(beam: Beam, map: &Input, movements: &mut HashSet<Beam>) {
             );
             traverse(
                 Beam {
-                    direction: BeamDirection::Right,
+                    direction: Direction4::East,
                     position: (beam.position.0, beam.position.1 + 1),
                 },
                 map,
                 movements,
             );
         }
-        (BeamDirection::Down, Tile::Empty) | (BeamDirection::Down, Tile::VerticalSplitter) => {
-            traverse(
-                Beam {
-                    direction: BeamDirection::Down,
-                    position: (beam.position.0 + 1, beam.position.1),
-                },
-                map,
-                movements,
-            )
-        }
-        (BeamDirection::Down, Tile::RightAngleMirror) => traverse(
+        (Direction4::South, Tile::Empty) | (Direction4::South, Tile::VerticalSplitter) => traverse(
             Beam {
-                direction: BeamDirection::Left,
+                direction: Direction4::South,
+                position: (beam.position.0 + 1, beam.position.1),
+            },
+            map,
+            movements,
+        ),
+        (Direction4::South, Tile::RightAngleMirror) => traverse(
+            Beam {
+                direction: Direction4::West,
                 position: (beam.position.0, beam.position.1 - 1),
             },
             map,
             movements,
         ),
-        (BeamDirection::Down, Tile::LeftAngleMirror) => traverse(
+        (Direction4::South, Tile::LeftAngleMirror) => traverse(
             Beam {
-                direction: BeamDirection::Right,
+                direction: Direction4::East,
                 position: (beam.position.0, beam.position.1 + 1),
             },
             map,
             movements,
         ),
-        (BeamDirection::Down, Tile::HorizontalSplitter) => {
+        (Direction4::South, Tile::HorizontalSplitter) => {
             traverse(
                 Beam {
-                    direction: BeamDirection::Left,
+                    direction: Direction4::West,
                     position: (beam.position.0, beam.position.1 - 1),
                 },
                 map,
@@ -204,43 +191,41 @@ fn traverse(beam: Beam, map: &Input, movements: &mut HashSet<Beam>) {
             );
             traverse(
                 Beam {
-                    direction: BeamDirection::Right,
+                    direction: Direction4::East,
                     position: (beam.position.0, beam.position.1 + 1),
                 },
                 map,
                 movements,
             );
         }
-        (BeamDirection::Left, Tile::Empty) | (BeamDirection::Left, Tile::HorizontalSplitter) => {
-            traverse(
-                Beam {
-                    direction: BeamDirection::Left,
-                    position: (beam.position.0, beam.position.1 - 1),
-                },
-                map,
-                movements,
-            )
-        }
-        (BeamDirection::Left, Tile::RightAngleMirror) => traverse(
+        (Direction4::West, Tile::Empty) | (Direction4::West, Tile::HorizontalSplitter) => traverse(
             Beam {
-                direction: BeamDirection::Down,
+                direction: Direction4::West,
+                position: (beam.position.0, beam.position.1 - 1),
+            },
+            map,
+            movements,
+        ),
+        (Direction4::West, Tile::RightAngleMirror) => traverse(
+            Beam {
+                direction: Direction4::South,
                 position: (beam.position.0 + 1, beam.position.1),
             },
             map,
             movements,
         ),
-        (BeamDirection::Left, Tile::LeftAngleMirror) => traverse(
+        (Direction4::West, Tile::LeftAngleMirror) => traverse(
             Beam {
-                direction: BeamDirection::Up,
+                direction: Direction4::North,
                 position: (beam.position.0 - 1, beam.position.1),
             },
             map,
             movements,
         ),
-        (BeamDirection::Left, Tile::VerticalSplitter) => {
+        (Direction4::West, Tile::VerticalSplitter) => {
             traverse(
                 Beam {
-                    direction: BeamDirection::Up,
+                    direction: Direction4::North,
                     position: (beam.position.0 - 1, beam.position.1),
                 },
                 map,
@@ -248,43 +233,41 @@ fn traverse(beam: Beam, map: &Input, movements: &mut HashSet<Beam>) {
             );
             traverse(
                 Beam {
-                    direction: BeamDirection::Down,
+                    direction: Direction4::South,
                     position: (beam.position.0 + 1, beam.position.1),
                 },
                 map,
                 movements,
             );
         }
-        (BeamDirection::Right, Tile::Empty) | (BeamDirection::Right, Tile::HorizontalSplitter) => {
-            traverse(
-                Beam {
-                    direction: BeamDirection::Right,
-                    position: (beam.position.0, beam.position.1 + 1),
-                },
-                map,
-                movements,
-            )
-        }
-        (BeamDirection::Right, Tile::RightAngleMirror) => traverse(
+        (Direction4::East, Tile::Empty) | (Direction4::East, Tile::HorizontalSplitter) => traverse(
             Beam {
-                direction: BeamDirection::Up,
+                direction: Direction4::East,
+                position: (beam.position.0, beam.position.1 + 1),
+            },
+            map,
+            movements,
+        ),
+        (Direction4::East, Tile::RightAngleMirror) => traverse(
+            Beam {
+                direction: Direction4::North,
                 position: (beam.position.0 - 1, beam.position.1),
             },
             map,
             movements,
         ),
-        (BeamDirection::Right, Tile::LeftAngleMirror) => traverse(
+        (Direction4::East, Tile::LeftAngleMirror) => traverse(
             Beam {
-                direction: BeamDirection::Down,
+                direction: Direction4::South,
                 position: (beam.position.0 + 1, beam.position.1),
             },
             map,
             movements,
         ),
-        (BeamDirection::Right, Tile::VerticalSplitter) => {
+        (Direction4::East, Tile::VerticalSplitter) => {
             traverse(
                 Beam {
-                    direction: BeamDirection::Up,
+                    direction: Direction4::North,
                     position: (beam.position.0 - 1, beam.position.1),
                 },
                 map,
@@ -292,7 +275,7 @@ fn traverse(beam: Beam, map: &Input, movements: &mut HashSet<Beam>) {
             );
             traverse(
                 Beam {
-                    direction: BeamDirection::Down,
+                    direction: Direction4::South,
                     position: (beam.position.0 + 1, beam.position.1),
                 },
                 map,
@@ -329,4 +312,9 @@ mod tests {
             51
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -1,6 +1,7 @@
 mod util;
 
-use pathfinding::directed::astar::astar;
+use aoc_util::geometry::{Direction4, Point2};
+use aoc_util::graph::shortest_paths_bucketed;
 use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
@@ -28,68 +29,44 @@ impl AoCDay<Input, Output> for Day {
     }
 
     fn part1(&self, cost_map: &Input) -> Result<Output, Box<dyn Error>> {
-        let target_y = cost_map.len() as isize - 1;
-        let target_x = cost_map.get(0).map(|r| r.len() as isize - 1).unwrap_or(0);
-
-        astar(
-            &START,
-            |state| state.get_successors(cost_map, 1, 3),
-            |state| state.manhatten_distance(target_y, target_x),
-            |state| state.success(target_y, target_x),
-        )
-        .map(|(_, total_cost)| total_cost)
-        .ok_or("No path found".into())
+        let target = target(cost_map);
+
+        shortest_paths_bucketed([START], |state| state.get_successors(cost_map, 1, 3))
+            .closest(|state| state.position == target)
+            .map(|(_, total_cost)| total_cost)
+            .ok_or("No path found".into())
     }
 
     fn part2(&self, cost_map: &Input) -> Result<Output, Box<dyn Error>> {
-        let target_y = cost_map.len() as isize - 1;
-        let target_x = cost_map.get(0).map(|r| r.len() as isize - 1).unwrap_or(0);
-
-        astar(
-            &START,
-            |state| state.get_successors(cost_map, 4, 10),
-            |state| state.manhatten_distance(target_y, target_x),
-            |state| state.success(target_y, target_x),
-        )
-        .map(|(_, total_cost)| total_cost)
-        .ok_or("No path found".into())
+        let target = target(cost_map);
+
+        shortest_paths_bucketed([START], |state| state.get_successors(cost_map, 4, 10))
+            .closest(|state| state.position == target)
+            .map(|(_, total_cost)| total_cost)
+            .ok_or("No path found".into())
     }
 }
 
+fn target(cost_map: &Input) -> Point2 {
+    Point2::new(
+        cost_map.first().map(|r| r.len() as isize - 1).unwrap_or(0),
+        cost_map.len() as isize - 1,
+    )
+}
+
 const START: State = State {
-    y: 0,
-    x: 0,
-    direction: Direction::Right,
+    position: Point2::new(0, 0),
+    direction: Direction4::East,
     direction_step_counter: 1,
 };
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 struct State {
-    y: isize,
-    x: isize,
-    direction: Direction,
+    position: Point2,
+    direction: Direction4,
     direction_step_counter: usize,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn opposite(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Down,
-            Direction::Down => Direction::Up,
-            Direction::Left => Direction::Right,
-            Direction::Right => Direction::Left,
-        }
-    }
-}
-
 impl State {
     fn get_successors(
         &self,
@@ -97,67 +74,37 @@ impl State {
         minimum_step_counter: usize,
         maximum_step_counter: usize,
     ) -> Vec<(State, usize)> {
-        [
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ]
-        .iter()
-        .filter(|&&direction| direction != self.direction.opposite())
-        .filter(|&&direction| {
-            direction == self.direction || self.direction_step_counter >= minimum_step_counter
-        })
-        .filter_map(|&direction| {
-            let direction_step_counter = if direction == self.direction {
-                self.direction_step_counter + 1
-            } else {
-                1
-            };
-
-            let y = self.y
-                + match direction {
-                    Direction::Up => -1,
-                    Direction::Down => 1,
-                    _ => 0,
-                };
-            let x = self.x
-                + match direction {
-                    Direction::Left => -1,
-                    Direction::Right => 1,
-                    _ => 0,
+        Direction4::ALL
+            .iter()
+            .filter(|&&direction| direction != self.direction.opposite())
+            .filter(|&&direction| {
+                direction == self.direction || self.direction_step_counter >= minimum_step_counter
+            })
+            .filter_map(|&direction| {
+                let direction_step_counter = if direction == self.direction {
+                    self.direction_step_counter + 1
+                } else {
+                    1
                 };
 
-            if direction_step_counter <= maximum_step_counter
-                && y >= 0
-                && x >= 0
-                && y < cost_map.len() as isize
-                && x < cost_map.get(0).map(|r| r.len() as isize).unwrap_or(0)
-            {
-                if let Some(cost) = cost_map.get(y as usize).and_then(|r| r.get(x as usize)) {
-                    return Some((
-                        State {
-                            y,
-                            x,
-                            direction,
-                            direction_step_counter,
-                        },
-                        *cost,
-                    ));
+                if direction_step_counter > maximum_step_counter {
+                    return None;
                 }
-            }
-
-            None
-        })
-        .collect()
-    }
-
-    fn manhatten_distance(&self, target_y: isize, target_x: isize) -> usize {
-        (self.y - target_y).unsigned_abs() + (self.x - target_x).unsigned_abs()
-    }
 
-    fn success(&self, target_y: isize, target_x: isize) -> bool {
-        self.y == target_y && self.x == target_x
+                let position = self.position.step(direction);
+                let (y, x) = position.checked_cast::<usize>()?.yx();
+                let cost = cost_map.get(y).and_then(|r| r.get(x))?;
+
+                Some((
+                    State {
+                        position,
+                        direction,
+                        direction_step_counter,
+                    },
+                    *cost,
+                ))
+            })
+            .collect()
     }
 }
 
@@ -188,4 +135,9 @@ mod tests {
             94
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
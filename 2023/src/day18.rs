@@ -1,5 +1,9 @@
 mod util;
 
+use aoc_util::{
+    geometry::{Direction4, Point2},
+    polygon::Polygon,
+};
 use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
@@ -9,19 +13,11 @@ type Output = isize;
 
 #[derive(Debug)]
 struct Movement {
-    direction: Direction,
+    direction: Direction4,
     distance: usize,
     color: Vec<char>,
 }
 
-#[derive(Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 struct Day {}
 
 impl AoCDay<Input, Output> for Day {
@@ -31,19 +27,20 @@ impl AoCDay<Input, Output> for Day {
             .map(|l| {
                 let l = l?;
 
-                let direction: Result<_, Box<dyn Error>> =
-                    match l.chars().next().ok_or("Empty line")? {
-                        'U' => Ok(Direction::Up),
-                        'D' => Ok(Direction::Down),
-                        'L' => Ok(Direction::Left),
-                        'R' => Ok(Direction::Right),
-                        _ => return Err("Invalid direction".into()),
-                    };
-                let direction = direction?;
-
-                let (step_str, color_str) = l[2..].split_once(' ').ok_or("Invalid line")?;
+                let direction = Direction4::from_char(l.chars().next().ok_or("Empty line")?)
+                    .ok_or("Invalid direction")?;
+
+                let (step_str, color_str) = l
+                    .get(2..)
+                    .and_then(|rest| rest.split_once(' '))
+                    .ok_or("Invalid line")?;
                 let distance = step_str.parse()?;
-                let color = color_str[2..color_str.len() - 1].chars().collect();
+                let color = color_str
+                    .strip_prefix("(#")
+                    .and_then(|c| c.strip_suffix(')'))
+                    .ok_or("Invalid color")?
+                    .chars()
+                    .collect();
 
                 Ok(Movement {
                     direction,
@@ -55,80 +52,39 @@ impl AoCDay<Input, Output> for Day {
     }
 
     fn part1(&self, movements: &Input) -> Result<Output, Box<dyn Error>> {
-        let mut pos = (0, 0);
+        let mut pos = Point2::new(0, 0);
         let mut edges = vec![];
 
-        let mut trenches = 0;
-
         for movement in movements {
             let distance = movement.distance as isize;
-            trenches += distance;
-            match movement.direction {
-                Direction::Up => {
-                    pos.0 -= distance;
-                    edges.push(pos);
-                }
-                Direction::Down => {
-                    pos.0 += distance;
-                    edges.push(pos);
-                }
-                Direction::Left => {
-                    pos.1 -= distance;
-                    edges.push(pos);
-                }
-                Direction::Right => {
-                    pos.1 += distance;
-                    edges.push(pos);
-                }
-            }
+            pos += movement.direction.delta() * distance;
+            edges.push(pos);
         }
 
-        Ok(shoelace_theorem(&edges) + trenches / 2 + 1)
+        Ok(Polygon::new(edges).enclosed_points() as isize)
     }
 
     fn part2(&self, movements: &Input) -> Result<Output, Box<dyn Error>> {
-        let mut pos = (0, 0);
+        let mut pos = Point2::new(0, 0);
         let mut edges = vec![];
 
-        let mut trenches = 0;
-
         for movement in movements {
             let distance = hex_chars_to_int(&movement.color[0..5]);
-            trenches += distance;
-            match movement.color[5] {
-                '3' => {
-                    pos.0 -= distance;
-                    edges.push(pos);
-                }
-                '1' => {
-                    pos.0 += distance;
-                    edges.push(pos);
-                }
-                '2' => {
-                    pos.1 -= distance;
-                    edges.push(pos);
-                }
-                '0' => {
-                    pos.1 += distance;
-                    edges.push(pos);
-                }
+            let direction = match movement.color[5] {
+                '3' => Direction4::North,
+                '1' => Direction4::South,
+                '2' => Direction4::West,
+                '0' => Direction4::East,
                 _ => return Err("Invalid direction".into()),
-            }
+            };
+            pos += direction.delta() * distance;
+            edges.push(pos);
         }
 
-        Ok(shoelace_theorem(&edges) + trenches / 2 + 1)
+        Ok(Polygon::new(edges).enclosed_points() as isize)
     }
 }
 
-fn shoelace_theorem(points: &[(isize, isize)]) -> isize {
-    points
-        .windows(2)
-        .map(|p| p[0].0 * p[1].1 - p[1].0 * p[0].1)
-        .sum::<isize>()
-        .abs()
-        / 2
-}
-
 fn hex_chars_to_int(hex: &[char]) -> isize {
     hex.iter()
         .map(|c| c.to_digit(16).unwrap_or(0) as isize)
@@ -162,4 +118,9 @@ mod tests {
             952408144115
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -185,7 +185,7 @@ fn parse_workflow(line: &String) -> Result<(String, Vec<Rule>), Box<dyn Error>>
                     comparisation: Some(Comparisation {
                         cat,
                         comperator,
-                        value: expression[2..].parse()?,
+                        value: expression.get(2..).ok_or("invalid expression")?.parse()?,
                     }),
                     target: target.to_string(),
                 })
@@ -208,8 +208,8 @@ fn parse_part(line: &String) -> Result<Part, Box<dyn Error>> {
     let line = line.strip_suffix('}').ok_or("no }")?;
     let values = line
         .split(',')
-        .map(|cat_value| cat_value[2..].parse())
-        .collect::<Result<Vec<_>, _>>()?;
+        .map(|cat_value| Ok(cat_value.get(2..).ok_or("invalid value")?.parse()?))
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
     #[allow(clippy::get_first)]
     let x = *values.get(0).ok_or("no x")?;
     let m = *values.get(1).ok_or("no x")?;
@@ -381,4 +381,9 @@ mod tests {
         assert_eq!(true_range, 2771..=4000);
         assert_eq!(false_range, 1351..=2770);
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
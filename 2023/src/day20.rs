@@ -1,5 +1,6 @@
 mod util;
 
+use aoc_util::number_theory::lcm_all;
 use std::collections::{HashSet, VecDeque};
 use std::fmt::Debug;
 use std::{collections::HashMap, error::Error, io::BufRead};
@@ -228,19 +229,7 @@ impl AoCDay<Input, Output> for Day {
             }
         }
 
-        Ok(counters.iter().fold(1, |a, b| lcm(a, *b)))
-    }
-}
-
-fn lcm(a: usize, b: usize) -> usize {
-    (a * b) / gcd(a, b)
-}
-
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
+        Ok(lcm_all(counters.iter().copied()))
     }
 }
 
@@ -271,4 +260,9 @@ mod tests {
             11687500
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
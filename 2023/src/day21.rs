@@ -1,6 +1,6 @@
 mod util;
 
-use pathfinding::directed::dijkstra::dijkstra_all;
+use aoc_util::graph::bfs_distances;
 use std::{collections::HashSet, error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
@@ -8,6 +8,7 @@ const ID: &str = "day21";
 type Input = InfiniteMap;
 type Output = usize;
 
+#[derive(Debug)]
 struct InfiniteMap {
     rocks: HashSet<(isize, isize)>,
     starting_position: (isize, isize),
@@ -67,15 +68,15 @@ impl InfiniteMap {
     // Solution without map-assertions, for low max_steps
     #[allow(dead_code)]
     fn count_reachable_garden_plots_dijkstra(&self, max_steps: isize) -> usize {
-        let reachable_nodes = dijkstra_all(
-            &(0, self.starting_position.0, self.starting_position.1),
+        let reachable_nodes = bfs_distances(
+            (0, self.starting_position.0, self.starting_position.1),
             |(steps, y, x)| {
                 let steps = steps + 1;
                 if steps <= max_steps {
                     vec![(*y - 1, *x), (*y + 1, *x), (*y, *x - 1), (*y, *x + 1)]
                         .into_iter()
                         .filter(|(y, x)| !self.rocks.contains(&(*y, *x)))
-                        .map(|(y, x)| ((steps, y, x), 0))
+                        .map(|(y, x)| (steps, y, x))
                         .collect::<Vec<_>>()
                 } else {
                     vec![]
@@ -128,6 +129,40 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_util::{
+        compare::{report, Alternatives},
+        fuzz::Rng,
+    };
+
+    // Square garden with the start in the middle. Counting the diamond assumes that every plot
+    // that isn't walled in can be reached on a shortest path, so rocks only go where both row and
+    // column are an odd distance away from the start: the rows and columns in between stay clear.
+    fn generate_map(size: isize, seed: u64) -> InfiniteMap {
+        let mut rng = Rng::new(seed);
+        let center = size / 2;
+        InfiniteMap {
+            rocks: (0..size)
+                .flat_map(|y| (0..size).map(move |x| (y, x)))
+                .filter(|&(y, x)| (y - center) % 2 != 0 && (x - center) % 2 != 0)
+                .filter(|_| rng.below(2) == 0)
+                .collect(),
+            starting_position: (center, center),
+            limits: (size - 1, size - 1),
+        }
+    }
+
+    // A map and the number of steps
+    type Case = (InfiniteMap, isize);
+
+    fn alternatives<'a>() -> Alternatives<'a, Case, usize> {
+        Alternatives::new()
+            .add("dijkstra", |(map, steps): &Case| {
+                map.count_reachable_garden_plots_dijkstra(*steps)
+            })
+            .add("diamond", |(map, steps): &Case| {
+                map.count_reachable_garden_plots(*steps)
+            })
+    }
 
     #[test]
     fn test_solve_part1() {
@@ -147,4 +182,25 @@ mod tests {
             0
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
+
+    #[test]
+    fn test_alternatives_agree() {
+        let inputs = (0..50)
+            .map(|n| (generate_map(21, n), n as isize % 9 + 1))
+            .collect::<Vec<_>>();
+        alternatives().check_all(&inputs).unwrap();
+    }
+
+    // cargo test --release --bin day21 bench -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_alternatives() {
+        let input = (generate_map(131, 0), 64);
+        println!("{}", report(&alternatives().bench(&input, 10)));
+    }
 }
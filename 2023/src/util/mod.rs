@@ -1,2 +1,3 @@
 pub(crate) mod aoc;
+pub(crate) mod fuzz;
 pub(crate) mod input;
@@ -20,8 +20,14 @@ impl AoCDay<Input, Output> for Day {
             let line = line?;
             let whitespace_split = line.split_whitespace().collect::<Vec<&str>>();
 
-            let num1 = whitespace_split[0].parse::<isize>()?;
-            let num2 = whitespace_split[1].parse::<isize>()?;
+            let num1 = whitespace_split
+                .first()
+                .ok_or("Invalid input")?
+                .parse::<isize>()?;
+            let num2 = whitespace_split
+                .get(1)
+                .ok_or("Invalid input")?
+                .parse::<isize>()?;
 
             list1.push(num1);
             list2.push(num2);
@@ -84,4 +90,9 @@ mod tests {
             31
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -1,85 +1,43 @@
 mod util;
 
+use aoc_util::geometry::{Direction4, Point2};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use std::{collections::HashSet, error::Error, hash::Hash, io::BufRead};
+use std::{collections::HashSet, error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day06";
 
-#[derive(PartialEq, Eq, Hash)]
-enum Direction {
-    N,
-    E,
-    S,
-    W,
-}
-
-impl Clone for Direction {
-    fn clone(&self) -> Self {
-        match self {
-            Direction::N => Direction::N,
-            Direction::E => Direction::E,
-            Direction::S => Direction::S,
-            Direction::W => Direction::W,
-        }
-    }
-}
-
-impl Direction {
-    fn turn_right(&self) -> Self {
-        match self {
-            Direction::N => Direction::E,
-            Direction::E => Direction::S,
-            Direction::S => Direction::W,
-            Direction::W => Direction::N,
-        }
-    }
-
-    fn move_forward(&self, position: (isize, isize)) -> (isize, isize) {
-        match self {
-            Direction::N => (position.0 - 1, position.1),
-            Direction::E => (position.0, position.1 + 1),
-            Direction::S => (position.0 + 1, position.1),
-            Direction::W => (position.0, position.1 - 1),
-        }
-    }
-}
-
 struct Map {
-    start_direction: Direction,
-    start_position: (isize, isize),
+    start_direction: Direction4,
+    start_position: Point2,
     obstacles: Vec<Vec<bool>>,
 }
 
 impl Map {
-    fn is_out_of_bounds(&self, position: (isize, isize)) -> bool {
-        position.0 < 0
-            || position.1 < 0
-            || position.0 >= self.obstacles[0].len() as isize
-            || position.1 >= self.obstacles.len() as isize
-    }
-
-    fn is_obstacle(&self, position: (isize, isize)) -> bool {
-        self.obstacles[position.0 as usize][position.1 as usize]
+    fn in_bounds(&self, position: Point2) -> Option<Point2<usize>> {
+        position.in_bounds(
+            self.obstacles.first().map_or(0, |row| row.len()),
+            self.obstacles.len(),
+        )
     }
 
     fn move_until_out_of_bounds_or_loop(
         &self,
-        additional_obstacle: Option<(isize, isize)>,
-    ) -> (bool, HashSet<(isize, isize, Direction)>) {
+        additional_obstacle: Option<Point2>,
+    ) -> (bool, HashSet<(Point2, Direction4)>) {
         let mut possition = self.start_position;
-        let mut direction = self.start_direction.clone();
+        let mut direction = self.start_direction;
         let mut visited_positions = HashSet::new();
-        visited_positions.insert((possition.0, possition.1, direction.clone()));
+        visited_positions.insert((possition, direction));
 
         loop {
             let mut next_position;
             loop {
-                next_position = direction.move_forward(possition);
-                if self.is_out_of_bounds(next_position) {
+                next_position = possition.step(direction);
+                let Some(index) = self.in_bounds(next_position) else {
                     return (false, visited_positions);
-                }
-                if self.is_obstacle(next_position) || Some(next_position) == additional_obstacle {
+                };
+                if self.obstacles[index.y][index.x] || Some(next_position) == additional_obstacle {
                     direction = direction.turn_right();
                 } else {
                     break;
@@ -89,11 +47,9 @@ impl Map {
             possition = next_position;
 
             // Loop Detection
-            if visited_positions.contains(&(possition.0, possition.1, direction.clone())) {
+            if !visited_positions.insert((possition, direction)) {
                 return (true, visited_positions);
             }
-
-            visited_positions.insert((possition.0, possition.1, direction.clone()));
         }
     }
 }
@@ -105,7 +61,7 @@ struct Day {}
 
 impl AoCDay<Input, Output> for Day {
     fn parse_input(&self, id: &str) -> Result<Input, Box<dyn Error>> {
-        let mut start_position = (0, 0);
+        let mut start_position = Point2::new(0, 0);
 
         let obstacles = get_reader(id)?
             .lines()
@@ -118,7 +74,7 @@ impl AoCDay<Input, Output> for Day {
                         '#' => Ok(true),
                         '.' => Ok(false),
                         '^' => {
-                            start_position = (y as isize, x as isize);
+                            start_position = Point2::new(x as isize, y as isize);
                             Ok(false)
                         }
                         _ => Err("Invalid input"),
@@ -128,7 +84,7 @@ impl AoCDay<Input, Output> for Day {
             .collect::<std::result::Result<_, _>>()?;
 
         Ok(Map {
-            start_direction: Direction::N,
+            start_direction: Direction4::North,
             start_position,
             obstacles,
         })
@@ -138,7 +94,7 @@ impl AoCDay<Input, Output> for Day {
         let (_, visited_positions_and_directions) = input.move_until_out_of_bounds_or_loop(None);
         Ok(visited_positions_and_directions
             .iter()
-            .map(|(y, x, _)| (y, x))
+            .map(|(position, _)| position)
             .collect::<HashSet<_>>()
             .len())
     }
@@ -147,13 +103,13 @@ impl AoCDay<Input, Output> for Day {
         let (_, visited_positions_and_directions) = input.move_until_out_of_bounds_or_loop(None);
         let visited_positions = visited_positions_and_directions
             .iter()
-            .map(|(y, x, _)| (y, x))
+            .map(|(position, _)| *position)
             .collect::<HashSet<_>>();
 
         Ok(visited_positions
             .par_iter()
-            .filter(|(&y, &x)| {
-                let (contains_loop, _) = input.move_until_out_of_bounds_or_loop(Some((y, x)));
+            .filter(|&&position| {
+                let (contains_loop, _) = input.move_until_out_of_bounds_or_loop(Some(position));
                 contains_loop
             })
             .count())
@@ -187,4 +143,9 @@ mod tests {
             6
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
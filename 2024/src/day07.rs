@@ -42,8 +42,8 @@ impl AoCDay<Input, Output> for Day {
                         |previous, part| {
                             let mut acc = Vec::with_capacity(previous.len() * 2);
                             previous.iter().filter(|&p| p <= total).for_each(|&p| {
-                                acc.push(p + part);
-                                acc.push(p * part);
+                                acc.extend(p.checked_add(*part));
+                                acc.extend(p.checked_mul(*part));
                             });
                             acc
                         },
@@ -68,9 +68,9 @@ impl AoCDay<Input, Output> for Day {
                         |previous, part| {
                             let mut acc = Vec::with_capacity(previous.len() * 3);
                             previous.iter().filter(|&p| p <= total).for_each(|&p| {
-                                acc.push(p + part);
-                                acc.push(p * part);
-                                acc.push(concat(p, *part));
+                                acc.extend(p.checked_add(*part));
+                                acc.extend(p.checked_mul(*part));
+                                acc.extend(concat(p, *part));
                             });
                             acc
                         },
@@ -83,12 +83,10 @@ impl AoCDay<Input, Output> for Day {
     }
 }
 
-fn concat(a: usize, b: usize) -> usize {
-    let shift = (1..)
-        .map(|i| 10_usize.pow(i))
-        .find(|&i| i > b)
-        .expect("Integer overflow");
-    a * shift + b
+// None on overflow, such a value can't match any total anyway.
+fn concat(a: usize, b: usize) -> Option<usize> {
+    let shift = 10_usize.checked_pow(b.checked_ilog10().unwrap_or(0) + 1)?;
+    a.checked_mul(shift)?.checked_add(b)
 }
 
 fn main() {
@@ -118,4 +116,9 @@ mod tests {
             11387
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
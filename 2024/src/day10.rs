@@ -145,6 +145,34 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_util::{
+        compare::{report, Alternatives},
+        fuzz::Rng,
+    };
+
+    // Noisy slopes, so that there are plenty of trails
+    fn generate_map(size: usize, seed: u64) -> Input {
+        let mut rng = Rng::new(seed);
+        (0..size)
+            .map(|y| {
+                (0..size)
+                    .map(|x| ((x + y + rng.below(3)) % 10) as u32)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn part2_alternatives<'a>() -> Alternatives<'a, Input, Output> {
+        Alternatives::new()
+            .add("dynamic", |map| Day {}.part2(map).unwrap())
+            .add("recursive", |map: &Input| {
+                (0..map.len())
+                    .flat_map(|y| (0..map[y].len()).map(move |x| (y, x)))
+                    .filter(|&(y, x)| map[y][x] == 0)
+                    .map(|position| recursive_part1(map, position).len())
+                    .sum()
+            })
+    }
 
     #[test]
     fn test_solve_part1() {
@@ -165,4 +193,25 @@ mod tests {
             81
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
+
+    #[test]
+    fn test_alternatives_agree() {
+        let maps = (0..50)
+            .map(|n| generate_map(n % 15 + 1, n as u64))
+            .collect::<Vec<_>>();
+        part2_alternatives().check_all(&maps).unwrap();
+    }
+
+    // cargo test --release --bin day10 bench -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_alternatives() {
+        let map = generate_map(50, 0);
+        println!("part2\n{}", report(&part2_alternatives().bench(&map, 100)));
+    }
 }
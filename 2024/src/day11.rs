@@ -1,6 +1,7 @@
 mod util;
 
-use std::{collections::HashMap, error::Error, io::BufRead};
+use aoc_util::{checked::Checked, memo::Memo};
+use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day11";
@@ -40,37 +41,35 @@ fn split_number(n: usize) -> Option<(usize, usize)> {
     }
 }
 
-fn map_number(x: usize) -> (usize, Option<usize>) {
+fn map_number(x: usize) -> Option<(usize, Option<usize>)> {
     if x == 0 {
-        return (1, None);
+        return Some((1, None));
     }
 
     match split_number(x) {
-        Some((a, b)) => (a, Some(b)),
-        None => (x * 2024, None),
+        Some((a, b)) => Some((a, Some(b))),
+        None => Some((x.checked_mul(2024)?, None)),
     }
 }
 
 fn len_after_blinks(
     number: usize,
     blinks_left: usize,
-    seen: &mut HashMap<(usize, usize), usize>,
-) -> usize {
+    seen: &mut Memo<(usize, usize), Checked<usize>>,
+) -> Checked<usize> {
     if blinks_left == 0 {
-        return 1;
+        return Checked::new(1);
     }
 
-    let key = (number, blinks_left);
-    if let Some(&known_len) = seen.get(&key) {
-        return known_len;
-    }
-
-    let (a, b_opt) = map_number(number);
-    let len = len_after_blinks(a, blinks_left - 1, seen)
-        + b_opt.map_or(0, |b| len_after_blinks(b, blinks_left - 1, seen));
-
-    seen.insert(key, len);
-    len
+    seen.get_or_compute((number, blinks_left), |seen| {
+        let Some((a, b_opt)) = map_number(number) else {
+            return Checked::overflow();
+        };
+        len_after_blinks(a, blinks_left - 1, seen)
+            + b_opt.map_or(Checked::new(0), |b| {
+                len_after_blinks(b, blinks_left - 1, seen)
+            })
+    })
 }
 
 struct Day {}
@@ -87,13 +86,21 @@ impl AoCDay<Input, Output> for Day {
     }
 
     fn part1(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
-        let seen = &mut HashMap::new();
-        Ok(input.iter().map(|x| len_after_blinks(*x, 25, seen)).sum())
+        let seen = &mut Memo::new();
+        Ok(input
+            .iter()
+            .map(|x| len_after_blinks(*x, 25, seen))
+            .sum::<Checked<usize>>()
+            .get()?)
     }
 
     fn part2(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
-        let seen = &mut HashMap::new();
-        Ok(input.iter().map(|x| len_after_blinks(*x, 75, seen)).sum())
+        let seen = &mut Memo::new();
+        Ok(input
+            .iter()
+            .map(|x| len_after_blinks(*x, 75, seen))
+            .sum::<Checked<usize>>()
+            .get()?)
     }
 }
 
@@ -124,4 +131,9 @@ mod tests {
             65601038650482
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -71,13 +71,16 @@ struct Day {}
 
 impl AoCDay<Input, Output> for Day {
     fn parse_input(&self, id: &str) -> Result<Input, Box<dyn Error>> {
+        // Counted, the same number may show up more than once
         get_reader(id)?
             .lines()
             .next()
             .ok_or("Invalid input")??
             .split(' ')
-            .map(|x| Ok((x.parse()?, 1)))
-            .collect()
+            .try_fold(HashMap::new(), |mut stones, x| {
+                *stones.entry(x.parse()?).or_default() += 1;
+                Ok(stones)
+            })
     }
 
     // ~215µs
@@ -126,4 +129,9 @@ mod tests {
             65601038650482
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -1,5 +1,6 @@
 mod util;
 
+use aoc_util::{bitset::BitGrid, geometry::Point2};
 use hashbrown::HashSet;
 use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
@@ -17,7 +18,7 @@ type Input = Vec<Vec<char>>;
 type Output = usize;
 
 fn extract_regions(map: &Input) -> Vec<(char, HashSet<(isize, isize)>)> {
-    let mut visited_points = HashSet::new();
+    let mut visited_points = BitGrid::new(map.first().map_or(0, |row| row.len()), map.len());
 
     map.iter()
         .enumerate()
@@ -28,7 +29,7 @@ fn extract_regions(map: &Input) -> Vec<(char, HashSet<(isize, isize)>)> {
                 .filter_map(|(x, c)| {
                     let x = x as isize;
 
-                    if visited_points.contains(&(y, x)) {
+                    if visited_points.contains(Point2::new(x as usize, y as usize)) {
                         return None;
                     }
 
@@ -36,11 +37,10 @@ fn extract_regions(map: &Input) -> Vec<(char, HashSet<(isize, isize)>)> {
                     let mut stack = vec![(y, x)];
 
                     while let Some((y, x)) = stack.pop() {
-                        if visited_points.contains(&(y, x)) {
+                        if !visited_points.insert(Point2::new(x as usize, y as usize)) {
                             continue;
                         }
 
-                        visited_points.insert((y, x));
                         region.insert((y, x));
 
                         if y > 0 && map[y as usize - 1][x as usize] == *c {
@@ -170,4 +170,9 @@ mod tests {
                 );
             });
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
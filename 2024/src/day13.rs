@@ -1,5 +1,6 @@
 mod util;
 
+use aoc_util::linear_system::cheapest_nonnegative_integer_solution;
 use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
@@ -11,7 +12,7 @@ struct Day {}
 
 impl AoCDay<Input, Output> for Day {
     fn parse_input(&self, id: &str) -> Result<Input, Box<dyn Error>> {
-        Ok(get_reader(id)?
+        get_reader(id)?
             .lines()
             .filter(|line| line.as_ref().is_ok_and(|line| !line.is_empty()))
             .map(|line| {
@@ -41,15 +42,18 @@ impl AoCDay<Input, Output> for Day {
             })
             .collect::<Result<Vec<_>, _>>()?
             .chunks(6)
-            .map(|ns| (ns[0], ns[1], ns[2], ns[3], ns[4], ns[5]))
-            .collect())
+            .map(|ns| match *ns {
+                [a, b, c, d, e, f] => Ok((a, b, c, d, e, f)),
+                _ => Err("Invalid input: Incomplete machine".into()),
+            })
+            .collect()
     }
 
     fn part1(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
         Ok(input
             .iter()
-            .filter_map(solve_linear_equation)
-            .filter(|(a, b)| a <= &100 && b <= &100 && a >= &0 && b >= &0)
+            .filter_map(cheapest_presses)
+            .filter(|(a, b)| a <= &100 && b <= &100)
             .map(|(a, b)| a * 3 + b)
             .sum())
     }
@@ -60,8 +64,7 @@ impl AoCDay<Input, Output> for Day {
             .map(|(x1, y1, x2, y2, x, y)| {
                 (*x1, *y1, *x2, *y2, 10000000000000 + x, 10000000000000 + y)
             })
-            .filter_map(|c| solve_linear_equation(&c))
-            .filter(|(a, b)| a >= &0 && b >= &0)
+            .filter_map(|c| cheapest_presses(&c))
             .map(|(a, b)| a * 3 + b)
             .sum())
     }
@@ -71,20 +74,17 @@ impl AoCDay<Input, Output> for Day {
   a * x1 + b * x2 = x
   a * y1 + b * y2 = y
 
-  https://en.wikipedia.org/wiki/Cramer%27s_rule
+  A press costs 3 tokens, B press 1 token.
 */
-fn solve_linear_equation(
-    (x1, y1, x2, y2, x, y): &(isize, isize, isize, isize, isize, isize),
+fn cheapest_presses(
+    &(x1, y1, x2, y2, x, y): &(isize, isize, isize, isize, isize, isize),
 ) -> Option<(isize, isize)> {
-    let a = (y2 * x - x2 * y) / (x1 * y2 - y1 * x2);
-    let b = (-y1 * x + x1 * y) / (x1 * y2 - y1 * x2);
-
-    // Since we work with integers, we need to check if the solution is correct
-    if a * x1 + b * x2 == *x {
-        Some((a, b))
-    } else {
-        None
-    }
+    let [a, b] = cheapest_nonnegative_integer_solution(
+        [[x1 as i128, x2 as i128], [y1 as i128, y2 as i128]],
+        [x as i128, y as i128],
+        [3, 1],
+    )?;
+    Some((a.try_into().ok()?, b.try_into().ok()?))
 }
 
 fn main() {
@@ -111,7 +111,12 @@ mod tests {
         assert_eq!(
             day.parse_and_solve_part2(format!("{}_test1", ID).as_str())
                 .unwrap(),
-            42
+            875318608908
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -1,10 +1,11 @@
 mod util;
 
+use aoc_util::geometry::Direction4;
 use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day15";
-type Input = ((usize, usize), Vec<Vec<Cell>>, Vec<Direction>);
+type Input = ((usize, usize), Vec<Vec<Cell>>, Vec<Direction4>);
 type Output = usize;
 
 #[derive(Clone, Debug)]
@@ -16,23 +17,12 @@ enum Cell {
     Empty,
 }
 
-#[derive(Debug)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Direction {
-    fn new_position(&self, pos: &(usize, usize)) -> (usize, usize) {
-        match self {
-            Direction::North => (pos.0 - 1, pos.1),
-            Direction::East => (pos.0, pos.1 + 1),
-            Direction::South => (pos.0 + 1, pos.1),
-            Direction::West => (pos.0, pos.1 - 1),
-        }
-    }
+fn new_position(direction: Direction4, (y, x): (usize, usize)) -> (usize, usize) {
+    let delta = direction.delta::<isize>();
+    (
+        y.wrapping_add_signed(delta.y),
+        x.wrapping_add_signed(delta.x),
+    )
 }
 
 struct Day {}
@@ -74,10 +64,10 @@ impl AoCDay<Input, Output> for Day {
             .skip_while(|line| !line.is_empty())
             .flat_map(|line| {
                 line.chars().map(|c| match c {
-                    '>' => Ok(Direction::East),
-                    '<' => Ok(Direction::West),
-                    'v' => Ok(Direction::South),
-                    '^' => Ok(Direction::North),
+                    '>' => Ok(Direction4::East),
+                    '<' => Ok(Direction4::West),
+                    'v' => Ok(Direction4::South),
+                    '^' => Ok(Direction4::North),
                     _ => Err::<_, Box<dyn Error>>("Invalid input".into()),
                 })
             })
@@ -91,26 +81,26 @@ impl AoCDay<Input, Output> for Day {
         let mut map = map.clone();
 
         for direction in directions {
-            let new_pos = direction.new_position(&robot);
+            let new_pos = new_position(*direction, robot);
 
             match map[new_pos.0][new_pos.1] {
                 Cell::Empty => robot = new_pos,
                 Cell::Box => {
                     if let Some(new_box_pos) = match direction {
-                        Direction::North => (0..new_pos.0)
+                        Direction4::North => (0..new_pos.0)
                             .rev()
                             .take_while(|y| !matches!(map[*y][new_pos.1], Cell::Wall))
                             .find(|y| matches!(map[*y][new_pos.1], Cell::Empty))
                             .map(|y| (y, new_pos.1)),
-                        Direction::East => (new_pos.1 + 1..)
+                        Direction4::East => (new_pos.1 + 1..)
                             .take_while(|x| !matches!(map[new_pos.0][*x], Cell::Wall))
                             .find(|x| matches!(map[new_pos.0][*x], Cell::Empty))
                             .map(|x| (new_pos.0, x)),
-                        Direction::South => (new_pos.0 + 1..)
+                        Direction4::South => (new_pos.0 + 1..)
                             .take_while(|y| !matches!(map[*y][new_pos.1], Cell::Wall))
                             .find(|y| matches!(map[*y][new_pos.1], Cell::Empty))
                             .map(|y| (y, new_pos.1)),
-                        Direction::West => (0..new_pos.1)
+                        Direction4::West => (0..new_pos.1)
                             .rev()
                             .take_while(|x| !matches!(map[new_pos.0][*x], Cell::Wall))
                             .find(|x| matches!(map[new_pos.0][*x], Cell::Empty))
@@ -133,17 +123,17 @@ impl AoCDay<Input, Output> for Day {
         let mut map = double_map(map);
 
         for direction in directions {
-            let new_pos = direction.new_position(&robot);
+            let new_pos = new_position(*direction, robot);
 
             match map[new_pos.0][new_pos.1] {
                 Cell::Empty => robot = new_pos,
                 Cell::LeftBox | Cell::RightBox => {
                     if let Some(mut boxes_to_move) = double_box_dfs(&map, &new_pos, direction) {
                         boxes_to_move.sort_by(|a, b| match direction {
-                            Direction::North => a.0.cmp(&b.0).then(a.1.cmp(&b.1)),
-                            Direction::West => a.1.cmp(&b.1).then(a.0.cmp(&b.0)),
-                            Direction::South => b.0.cmp(&a.0).then(b.1.cmp(&a.1)),
-                            Direction::East => b.1.cmp(&a.1).then(b.0.cmp(&a.0)),
+                            Direction4::North => a.0.cmp(&b.0).then(a.1.cmp(&b.1)),
+                            Direction4::West => a.1.cmp(&b.1).then(a.0.cmp(&b.0)),
+                            Direction4::South => b.0.cmp(&a.0).then(b.1.cmp(&a.1)),
+                            Direction4::East => b.1.cmp(&a.1).then(b.0.cmp(&a.0)),
                         });
 
                         boxes_to_move
@@ -154,7 +144,7 @@ impl AoCDay<Input, Output> for Day {
                             })
                             .for_each(|(_, old_box_pos)| {
                                 let cell = map[old_box_pos.0][old_box_pos.1].clone();
-                                let new_box_pos = direction.new_position(old_box_pos);
+                                let new_box_pos = new_position(*direction, *old_box_pos);
                                 map[old_box_pos.0][old_box_pos.1] = Cell::Empty;
                                 map[new_box_pos.0][new_box_pos.1] = cell;
                             });
@@ -201,7 +191,7 @@ fn double_map(map: &[Vec<Cell>]) -> Vec<Vec<Cell>> {
 fn double_box_dfs(
     map: &[Vec<Cell>],
     pos: &(usize, usize),
-    direction: &Direction,
+    direction: &Direction4,
 ) -> Option<Vec<(usize, usize)>> {
     let pos_cell = &map[pos.0][pos.1];
 
@@ -213,7 +203,7 @@ fn double_box_dfs(
     };
 
     match direction {
-        &Direction::East => (pos.1 + 1..)
+        Direction4::East => (pos.1 + 1..)
             .take_while(|x| !matches!(map[pos.0][*x], Cell::Wall))
             .find(|x| matches!(map[pos.0][*x], Cell::Empty))
             .map(|x| {
@@ -222,7 +212,7 @@ fn double_box_dfs(
                     .chain([*pos])
                     .collect::<Vec<_>>()
             }),
-        &Direction::West => (0..pos.1)
+        Direction4::West => (0..pos.1)
             .rev()
             .take_while(|x| !matches!(map[pos.0][*x], Cell::Wall))
             .find(|x| matches!(map[pos.0][*x], Cell::Empty))
@@ -233,9 +223,9 @@ fn double_box_dfs(
                     .chain([*pos])
                     .collect::<Vec<_>>()
             }),
-        direction => double_box_dfs(map, &direction.new_position(&left_pos), direction).map(
+        direction => double_box_dfs(map, &new_position(*direction, left_pos), direction).map(
             |left_results| {
-                double_box_dfs(map, &direction.new_position(&right_pos), direction).map(
+                double_box_dfs(map, &new_position(*direction, right_pos), direction).map(
                     |right_results| {
                         [left_pos, right_pos]
                             .iter()
@@ -302,4 +292,9 @@ mod tests {
             9021
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -1,116 +1,69 @@
 mod util;
 
+use aoc_util::{geometry::Direction4, graph::shortest_paths_bucketed, parse::parse_grid};
 use hashbrown::HashSet;
-use pathfinding::prelude::{astar_bag, dijkstra};
 
-use std::{error::Error, io::BufRead};
+use std::{error::Error, io};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day16";
 type Input = ((usize, usize), (usize, usize), Vec<Vec<bool>>);
 type Output = usize;
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-enum Direction {
-    N,
-    E,
-    S,
-    W,
-}
-
-impl Direction {
-    fn turn_left(&self) -> Self {
-        match self {
-            Direction::N => Direction::W,
-            Direction::E => Direction::N,
-            Direction::S => Direction::E,
-            Direction::W => Direction::S,
-        }
-    }
-
-    fn turn_right(&self) -> Self {
-        match self {
-            Direction::N => Direction::E,
-            Direction::E => Direction::S,
-            Direction::S => Direction::W,
-            Direction::W => Direction::N,
-        }
-    }
-
-    fn move_forward(&self, position: (usize, usize)) -> (usize, usize) {
-        match self {
-            Direction::N => (position.0 - 1, position.1),
-            Direction::E => (position.0, position.1 + 1),
-            Direction::S => (position.0 + 1, position.1),
-            Direction::W => (position.0, position.1 - 1),
-        }
-    }
-}
-
 struct Day {}
 
 impl AoCDay<Input, Output> for Day {
     fn parse_input(&self, id: &str) -> Result<Input, Box<dyn Error>> {
-        let mut start = (0, 0);
-        let mut end = (0, 0);
-        let map = get_reader(id)?
-            .lines()
-            .enumerate()
-            .map(|(y, line)| {
-                let line = line?;
-                line.chars()
-                    .enumerate()
-                    .inspect(|(x, c)| match c {
-                        'S' => start = (y, *x),
-                        'E' => end = (y, *x),
-                        _ => {}
-                    })
-                    .map(|(_, c)| match c {
-                        '#' => Ok(false),
-                        '.' | 'S' | 'E' => Ok(true),
-                        _ => Err::<_, Box<dyn Error>>("Invalid input".into()),
-                    })
-                    .collect::<Result<Vec<_>, _>>()
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok((start, end, map))
+        let input = io::read_to_string(get_reader(id)?)?;
+        let grid = parse_grid(&input, &['S', 'E'], |c| match c {
+            '#' => Some(false),
+            '.' | 'S' | 'E' => Some(true),
+            _ => None,
+        })?;
+        let start = grid.marker('S').ok_or("Invalid input: no start")?;
+        let end = grid.marker('E').ok_or("Invalid input: no end")?;
+
+        Ok((start.yx(), end.yx(), grid.cells))
     }
 
     fn part1(&self, (start, end, map): &Input) -> Result<Output, Box<dyn Error>> {
-        dijkstra(
-            &(start.0, start.1, Direction::E),
-            |state| sucessors(state, map),
-            |&(y, x, _)| (y, x) == *end,
-        )
+        shortest_paths_bucketed([(start.0, start.1, Direction4::East)], |state| {
+            sucessors(state, map)
+        })
+        .closest(|&(y, x, _)| (y, x) == *end)
         .map(|(_, cost)| cost)
         .ok_or("No path found".into())
     }
 
     fn part2(&self, (start, end, map): &Input) -> Result<Output, Box<dyn Error>> {
-        let best_paths = astar_bag(
-            &(start.0, start.1, Direction::E),
-            |state| sucessors(state, map),
-            |_| 0,
-            |&(y, x, _)| (y, x) == *end,
-        )
-        .ok_or("No path found")?;
-
-        Ok(best_paths
-            .0
-            .flat_map(|path| path.iter().map(|(y, x, _)| (*y, *x)).collect::<Vec<_>>())
-            .collect::<HashSet<_>>()
-            .len())
+        let paths = shortest_paths_bucketed([(start.0, start.1, Direction4::East)], |state| {
+            sucessors(state, map)
+        });
+        let targets = Direction4::ALL.map(|direction| (end.0, end.1, direction));
+
+        let tiles = paths
+            .nodes_on_shortest_paths(&targets)
+            .into_iter()
+            .map(|(y, x, _)| (y, x))
+            .collect::<HashSet<_>>();
+        if tiles.is_empty() {
+            return Err("No path found".into());
+        }
+        Ok(tiles.len())
     }
 }
 
 fn sucessors(
-    (y, x, direction): &(usize, usize, Direction),
+    (y, x, direction): &(usize, usize, Direction4),
     map: &[Vec<bool>],
-) -> Vec<((usize, usize, Direction), usize)> {
+) -> Vec<((usize, usize, Direction4), usize)> {
     let turned_left = ((*y, *x, direction.turn_left()), 1000);
     let turned_right = ((*y, *x, direction.turn_right()), 1000);
-    let next_position: (usize, usize) = direction.move_forward((*y, *x));
+    let delta = direction.delta::<isize>();
+    let next_position = (
+        y.wrapping_add_signed(delta.y),
+        x.wrapping_add_signed(delta.x),
+    );
     if let Some(Some(Some(moved_to_empty_space))) = map.get(next_position.0).map(|row| {
         row.get(next_position.1).map(|cell| {
             if *cell {
@@ -173,4 +126,9 @@ mod tests {
             64
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -0,0 +1,65 @@
+// day11 (memoized recursion) and day11_map (counts per stone number) are separate binaries,
+// so they are compared by running them on the same generated input files.
+use aoc_util::{
+    compare::{report, Alternatives},
+    fuzz::Rng,
+};
+use std::{
+    env, fs,
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static INPUTS: AtomicUsize = AtomicUsize::new(0);
+
+// The answers printed by the binary, one per part.
+fn run(binary: &str, stones: &String) -> Vec<String> {
+    let path = env::temp_dir().join(format!(
+        "aoc-2024-day11-{}-{}.txt",
+        std::process::id(),
+        INPUTS.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&path, stones).expect("can't write input");
+    let output = Command::new(binary)
+        .env("INPUT", &path)
+        .output()
+        .expect("can't run day");
+    let _ = fs::remove_file(&path);
+    assert!(output.status.success(), "{} failed on {}", binary, stones);
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once("): ").map(|(_, answer)| answer.to_string()))
+        .collect()
+}
+
+fn generate_stones(seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    (0..=rng.below(8))
+        .map(|_| match rng.below(3) {
+            0 => rng.below(10),
+            1 => rng.below(10_000),
+            _ => rng.below(10_000_000),
+        })
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn alternatives<'a>() -> Alternatives<'a, String, Vec<String>> {
+    Alternatives::new()
+        .add("memo", |stones| run(env!("CARGO_BIN_EXE_day11"), stones))
+        .add("map", |stones| run(env!("CARGO_BIN_EXE_day11_map"), stones))
+}
+
+#[test]
+fn test_day11_alternatives_agree() {
+    let inputs = (0..20).map(generate_stones).collect::<Vec<_>>();
+    alternatives().check_all(&inputs).unwrap();
+}
+
+// Includes starting the process. cargo test --release bench -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_day11_alternatives() {
+    println!("{}", report(&alternatives().bench(&generate_stones(0), 20)));
+}
@@ -1,6 +1,7 @@
 mod util;
 
-use std::{collections::HashSet, error::Error, io::BufRead};
+use aoc_util::{bitset::BitGrid, geometry::Point2};
+use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day04";
@@ -53,7 +54,8 @@ impl AoCDay<Input, Output> for Day {
     }
 
     fn part2(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
-        Ok(part2_rec(input, HashSet::new()))
+        let width = input.first().map_or(0, |row| row.len());
+        Ok(part2_rec(input, BitGrid::new(width, input.len())))
     }
 }
 
@@ -70,7 +72,7 @@ fn get_surroundings(y: isize, x: isize) -> [(isize, isize); 8] {
     ]
 }
 
-fn part2_rec(input: &Vec<Vec<bool>>, mut removed: HashSet<(usize, usize)>) -> usize {
+fn part2_rec(input: &Vec<Vec<bool>>, mut removed: BitGrid) -> usize {
     match input
         .iter()
         .enumerate()
@@ -79,7 +81,7 @@ fn part2_rec(input: &Vec<Vec<bool>>, mut removed: HashSet<(usize, usize)>) -> us
                 .enumerate()
                 .filter(|(_, is_tp)| **is_tp)
                 .filter(|(x, _)| {
-                    if removed.contains(&(y, *x)) {
+                    if removed.contains(Point2::new(*x, y)) {
                         return false;
                     }
                     let y = y as isize;
@@ -90,7 +92,7 @@ fn part2_rec(input: &Vec<Vec<bool>>, mut removed: HashSet<(usize, usize)>) -> us
                         .filter(|(y, x)| match input.get(*y as usize) {
                             Some(row) => {
                                 row.get(*x as usize) == Some(&true)
-                                    && !removed.contains(&(*y as usize, *x as usize))
+                                    && !removed.contains(Point2::new(*x as usize, *y as usize))
                             }
                             None => false,
                         })
@@ -98,7 +100,7 @@ fn part2_rec(input: &Vec<Vec<bool>>, mut removed: HashSet<(usize, usize)>) -> us
                         .count()
                         < 4
                     {
-                        removed.insert((y as usize, x as usize));
+                        removed.insert(Point2::new(x as usize, y as usize));
                         true
                     } else {
                         false
@@ -140,4 +142,9 @@ mod tests {
             43
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
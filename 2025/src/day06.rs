@@ -33,22 +33,24 @@ impl AoCDay<Input, Output> for Day {
             .collect::<Vec<_>>();
 
         let mut previous_pos = 0;
-        Ok(problem_pos
+        problem_pos
             .iter()
             .map(|(problem_pos, problem)| {
                 let from = previous_pos;
-                let to = problem_pos - previous_pos + 1;
+                let to = (problem_pos + 1)
+                    .checked_sub(previous_pos)
+                    .ok_or("Invalid input: No space between problems")?;
                 previous_pos = *problem_pos + 2;
-                (
+                Ok((
                     *problem,
                     lines
                         .iter()
                         .take(lines.len() - 1)
                         .map(|l| l.chars().rev().skip(from).take(to).collect())
                         .collect(),
-                )
+                ))
             })
-            .collect())
+            .collect()
     }
 
     fn part1(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
@@ -122,4 +124,9 @@ mod tests {
             3263827
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
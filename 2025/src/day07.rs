@@ -27,7 +27,10 @@ impl AoCDay<Input, Output> for Day {
 
         for (x, l) in lines.enumerate() {
             for (y, _) in l?.chars().enumerate().filter(|(_, c)| *c == '^') {
-                splitters[y].push(x + 1);
+                splitters
+                    .get_mut(y)
+                    .ok_or("Invalid input: Line longer than the first")?
+                    .push(x + 1);
             }
         }
 
@@ -108,4 +111,9 @@ mod tests {
             40
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
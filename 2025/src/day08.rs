@@ -1,6 +1,7 @@
 mod util;
 
-use std::{collections::HashSet, error::Error, io::BufRead};
+use aoc_util::{spatial::KdTree, union_find::UnionFind};
+use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day08";
@@ -30,98 +31,45 @@ impl AoCDay<Input, Output> for Day {
             _ => 1000,
         };
 
-        let jb_distances = measure_distances(input);
-
-        let mut circuits: Vec<HashSet<&[usize; 3]>> = vec![];
-
-        for (_, jb_a, jb_b) in jb_distances.iter().take(limit) {
-            connect(&mut circuits, jb_a, jb_b)?;
+        let mut circuits = UnionFind::new(input.len());
+        for (a, b) in closest_pairs(&build_tree(input), limit) {
+            circuits.union(a, b);
         }
 
-        circuits.sort_by(|s1, s2| s2.len().cmp(&s1.len()));
-
-        Ok(circuits.iter().take(3).map(|s| s.len()).product())
+        Ok(circuits.largest_component_sizes(3).iter().product())
     }
 
     fn part2(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
-        let jb_distances = measure_distances(input);
-
-        let mut circuits: Vec<HashSet<&[usize; 3]>> = vec![];
-
-        for (_, jb_a, jb_b) in jb_distances.iter().cycle() {
-            connect(&mut circuits, jb_a, jb_b)?;
-
-            if let Some(circuit) = circuits.get(0) {
-                if circuit.len() == input.len() {
-                    return Ok(jb_a[0] * jb_b[0]);
-                }
+        let tree = build_tree(input);
+        let all_pairs = input.len() * input.len().saturating_sub(1) / 2;
+
+        // The connecting pair is usually among the closest few thousand, only fetch more if not
+        let mut k = input.len();
+        loop {
+            let mut circuits = UnionFind::new(input.len());
+            if let Some((a, b)) = circuits.union_until_connected(closest_pairs(&tree, k)) {
+                return Ok(input[a][0] * input[b][0]);
             }
-        }
-        Err("Unexpected loop exit".into())
-    }
-}
-
-fn connect<'a>(
-    circuits: &mut Vec<HashSet<&'a [usize; 3]>>,
-    jb_a: &'a [usize; 3],
-    jb_b: &'a [usize; 3],
-) -> Result<(), Box<dyn Error + 'static>> {
-    let jb_a_i = circuits
-        .iter()
-        .enumerate()
-        .find(|(_, circuit)| circuit.contains(jb_a))
-        .map(|(jb_a_i, _)| jb_a_i);
-    let jb_b_j = circuits
-        .iter()
-        .enumerate()
-        .find(|(_, circuit)| circuit.contains(jb_b))
-        .map(|(jb_b_j, _)| jb_b_j);
-
-    Ok(match (jb_a_i, jb_b_j) {
-        (Some(i), Some(j)) => {
-            if i != j {
-                let set_to_merge = circuits.remove(i.max(j));
-                circuits[i.min(j)].extend(set_to_merge);
+            if k >= all_pairs {
+                return Err("Junction boxes never form a single circuit".into());
             }
+            k *= 2;
         }
-        (Some(i), None) => {
-            circuits
-                .get_mut(i)
-                .ok_or("Failed to access circuit contains_jb_a_i")?
-                .insert(jb_b);
-        }
-        (None, Some(j)) => {
-            circuits
-                .get_mut(j)
-                .ok_or("Failed to access circuit contains_jb_b_j")?
-                .insert(jb_a);
-        }
-        (None, None) => circuits.push(HashSet::from([jb_a, jb_b])),
-    })
+    }
 }
 
-fn measure_distances(input: &Vec<[usize; 3]>) -> Vec<(f64, &[usize; 3], &[usize; 3])> {
-    let mut jb_distances = input
-        .iter()
-        .enumerate()
-        .flat_map(|(i, jb)| {
-            input
-                .iter()
-                .skip(i)
-                .filter(move |&other| other != jb)
-                .map(move |other| (euclidian_distance(jb, other), jb, other))
-        })
-        .collect::<Vec<_>>();
-
-    jb_distances.sort_by(|(d1, _, _), (d2, _, _)| d1.total_cmp(d2));
-    jb_distances
+fn build_tree(input: &Input) -> KdTree<3> {
+    KdTree::new(
+        &input
+            .iter()
+            .map(|p| p.map(|c| c as i64))
+            .collect::<Vec<_>>(),
+    )
 }
 
-fn euclidian_distance(p: &[usize; 3], q: &[usize; 3]) -> f64 {
-    let xd = (p[0] as isize - q[0] as isize).pow(2);
-    let yd = (p[1] as isize - q[1] as isize).pow(2);
-    let zd = (p[2] as isize - q[2] as isize).pow(2);
-    ((xd + yd + zd) as f64).sqrt()
+// Pairs of junction boxes by index, closest first
+fn closest_pairs(tree: &KdTree<3>, k: usize) -> impl Iterator<Item = (usize, usize)> {
+    tree.closest_pairs(k).into_iter().map(|(_, a, b)| (a, b))
 }
 
 fn main() {
@@ -151,4 +99,9 @@ mod tests {
             25272
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
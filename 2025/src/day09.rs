@@ -1,7 +1,8 @@
 mod util;
 
+use aoc_util::{geometry::Point2, polygon::Polygon};
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
-use std::{collections::HashSet, error::Error, io::BufRead};
+use std::{error::Error, io::BufRead};
 use util::{aoc::AoCDay, input::get_reader};
 
 const ID: &str = "day09";
@@ -41,99 +42,24 @@ impl AoCDay<Input, Output> for Day {
     }
 
     fn part2(&self, input: &Input) -> Result<Output, Box<dyn Error>> {
-        !unimplemented!("Not correct");
-
-        let mut max_y = 0;
-        let mut max_x = 0;
-
-        let border_points = input
-            .windows(2)
-            .flat_map(|pairs| {
-                let y1 = pairs[0].0;
-                let x1 = pairs[0].1;
-                let y2 = pairs[1].0;
-                let x2 = pairs[1].1;
-
-                max_y = max_y.max(y1);
-                max_y = max_y.max(y2);
-                max_x = max_x.max(x1);
-                max_x = max_x.max(x2);
-
-                if y1 == y2 {
-                    (x1.min(x2)..=x2.max(x1))
-                        .map(|x| (y1, x))
-                        .collect::<Vec<_>>()
-                } else {
-                    (y1.min(y2)..=y2.max(y1)).map(|y| (y, x1)).collect()
-                }
-            })
-            .collect::<HashSet<(isize, isize)>>();
-
-        max_y += 1;
-        max_x += 1;
+        let tiles = Polygon::new(
+            input
+                .iter()
+                .map(|&(y, x)| Point2::from_yx((y, x)))
+                .collect(),
+        );
+        let corners = tiles.vertices();
 
-        input
-            .iter()
+        corners
+            .par_iter()
             .enumerate()
-            .map(|(i, (y1, x1))| {
-                //println!("Calculating input #{}", i);
-                input
-                    .par_iter()
+            .flat_map_iter(|(i, c1)| {
+                let tiles = &tiles;
+                corners
+                    .iter()
                     .skip(i)
-                    .filter(|(y2, x2)| {
-                        // check if inside of borders by calulating other two corners of rect
-                        // walk corners north, east, south, west
-                        // if we hit a position in the hashset the corner is inside
-                        // if we go outside of the max the corner is outside
-                        // if all corners are inside, continue
-
-                        let c3 = (y1.abs(), x2.abs());
-                        let c4 = (y2.abs(), x1.abs());
-
-                        for (y, x) in [c3, c4] {
-                            // north
-                            for walking_y in (0..=y).rev() {
-                                if walking_y == 0 {
-                                    return false;
-                                }
-                                if border_points.contains(&(walking_y, x)) {
-                                    break;
-                                }
-                            }
-                            // south
-                            for walking_y in y..=max_y {
-                                if walking_y == max_y {
-                                    return false;
-                                }
-                                if border_points.contains(&(walking_y, x)) {
-                                    break;
-                                }
-                            }
-                            // west
-                            for walking_x in (0..=x).rev() {
-                                if walking_x == 0 {
-                                    return false;
-                                }
-                                if border_points.contains(&(y, walking_x)) {
-                                    break;
-                                }
-                            }
-                            // east
-                            for walking_x in x..=max_x {
-                                if walking_x == max_x {
-                                    return false;
-                                }
-                                if border_points.contains(&(y, walking_x)) {
-                                    break;
-                                }
-                            }
-                        }
-
-                        true
-                    })
-                    .map(|(y2, x2)| ((y2 - y1).abs() + 1) * ((x2 - x1).abs() + 1))
-                    .max()
-                    .unwrap_or(0)
+                    .filter(move |c2| tiles.contains_rectangle(*c1, **c2))
+                    .map(move |c2| ((c2.y - c1.y).abs() + 1) * ((c2.x - c1.x).abs() + 1))
             })
             .max()
             .ok_or("No max found".into())
@@ -167,4 +93,9 @@ mod tests {
             24
         );
     }
+
+    #[test]
+    fn test_parse_input_robustness() {
+        util::fuzz::check_parse_input(ID, |id| Day {}.parse_input(id));
+    }
 }
@@ -0,0 +1,37 @@
+use aoc_util::fuzz::{Settings, check_parser};
+use std::error::Error;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Feeds parse_input mutated versions of the day's examples (inputs/{id}_testN.txt) through
+// temporary files, passed by absolute path so INPUT doesn't apply, and panics if it ever
+// panics or hangs instead of returning an error.
+#[allow(dead_code)]
+pub(crate) fn check_parse_input<I: 'static>(
+    id: &str,
+    parse: fn(&str) -> Result<I, Box<dyn Error>>,
+) {
+    let examples = (1..)
+        .map(|n| fs::read_to_string(format!("inputs/{}_test{}.txt", id, n)))
+        .map_while(Result::ok)
+        .collect::<Vec<_>>();
+    assert!(!examples.is_empty(), "no examples for {}", id);
+
+    // One directory per day, the tests of a process run in parallel
+    let dir = std::env::temp_dir().join(format!("aoc-fuzz-{}-{}", std::process::id(), id));
+    fs::create_dir_all(&dir).expect("can't create fuzz directory");
+    let prefix = dir.join(id).to_string_lossy().into_owned();
+    let counter = AtomicUsize::new(0);
+    let result = check_parser(&examples, &Settings::from_env(), move |input| {
+        let file_id = format!("{}_{}", prefix, counter.fetch_add(1, Ordering::Relaxed));
+        let path = format!("{}.txt", file_id);
+        fs::write(&path, input).expect("can't write fuzz input");
+        let result = parse(&file_id).map(|_| ());
+        let _ = fs::remove_file(&path);
+        result
+    });
+    let _ = fs::remove_dir_all(&dir);
+    if let Err(failure) = result {
+        panic!("parse_input {}", failure);
+    }
+}
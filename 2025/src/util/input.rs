@@ -2,12 +2,23 @@ use std::env;
 use std::fs::File;
 use std::io::BufReader;
 use std::io::Result as IoResult;
+use std::path::{Path, PathBuf};
 
+// id may also be an absolute path without the .txt extension. Those are read as they are,
+// INPUT only overrides the inputs of the day.
 #[allow(dead_code)]
 pub(crate) fn get_reader(id: &str) -> IoResult<BufReader<File>> {
+    if Path::new(id).is_absolute() {
+        return get_reader_from(format!("{}.txt", id));
+    }
     let path = match env::var("INPUT") {
-        Ok(val) => val,
-        Err(_) => format!("inputs/{}.txt", id),
+        Ok(val) => PathBuf::from(val),
+        Err(_) => Path::new("inputs").join(format!("{}.txt", id)),
     };
+    get_reader_from(path)
+}
+
+#[allow(dead_code)]
+pub(crate) fn get_reader_from<P: AsRef<Path>>(path: P) -> IoResult<BufReader<File>> {
     Ok(BufReader::new(File::open(path)?))
 }
@@ -0,0 +1,362 @@
+use crate::geometry::{Direction4, Point2};
+
+// Dense set of the integers 0..capacity, one bit each.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> Self {
+        BitSet {
+            words: vec![0; capacity.div_ceil(64)],
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // False if the value was already in the set, like HashSet::insert.
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(value < self.capacity, "{} out of range", value);
+        let (word, bit) = (value / 64, 1 << (value % 64));
+        let new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        new
+    }
+
+    pub fn remove(&mut self, value: usize) -> bool {
+        if value >= self.capacity {
+            return false;
+        }
+        let (word, bit) = (value / 64, 1 << (value % 64));
+        let present = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        present
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < self.capacity && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    // Keeps the allocation.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                if rest == 0 {
+                    return None;
+                }
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    fn zip_with(&mut self, other: &Self, f: impl Fn(u64, u64) -> u64) {
+        assert_eq!(self.capacity, other.capacity, "capacities differ");
+        for (a, &b) in self.words.iter_mut().zip(&other.words) {
+            *a = f(*a, b);
+        }
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a | b);
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a & b);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a & !b);
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(&a, &b)| a & !b == 0)
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+// Set of positions in a width x height grid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    bits: BitSet,
+    width: usize,
+    height: usize,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        BitGrid {
+            bits: BitSet::new(width * height),
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, position: Point2<usize>) -> Option<usize> {
+        (position.x < self.width && position.y < self.height)
+            .then_some(position.y * self.width + position.x)
+    }
+
+    pub fn insert(&mut self, position: Point2<usize>) -> bool {
+        let index = self.index(position).expect("position outside of the grid");
+        self.bits.insert(index)
+    }
+
+    pub fn remove(&mut self, position: Point2<usize>) -> bool {
+        self.index(position).is_some_and(|i| self.bits.remove(i))
+    }
+
+    // Positions outside of the grid are never contained.
+    pub fn contains(&self, position: Point2<usize>) -> bool {
+        self.index(position).is_some_and(|i| self.bits.contains(i))
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    // Row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Point2<usize>> + '_ {
+        self.bits
+            .iter()
+            .map(|i| Point2::new(i % self.width, i / self.width))
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.bits.union_with(&other.bits);
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.bits.intersect_with(&other.bits);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.bits.difference_with(&other.bits);
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.bits.is_subset(&other.bits)
+    }
+}
+
+impl Extend<Point2<usize>> for BitGrid {
+    fn extend<I: IntoIterator<Item = Point2<usize>>>(&mut self, iter: I) {
+        for position in iter {
+            self.insert(position);
+        }
+    }
+}
+
+// Set of (position, direction) states in a grid, e.g. to detect loops of a walker or beam.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StateBitSet {
+    bits: BitSet,
+    width: usize,
+    height: usize,
+}
+
+impl StateBitSet {
+    pub fn new(width: usize, height: usize) -> Self {
+        StateBitSet {
+            bits: BitSet::new(width * height * 4),
+            width,
+            height,
+        }
+    }
+
+    fn index(&self, position: Point2<usize>, direction: Direction4) -> Option<usize> {
+        (position.x < self.width && position.y < self.height)
+            .then_some((position.y * self.width + position.x) * 4 + direction as usize)
+    }
+
+    pub fn insert(&mut self, position: Point2<usize>, direction: Direction4) -> bool {
+        let index = self
+            .index(position, direction)
+            .expect("position outside of the grid");
+        self.bits.insert(index)
+    }
+
+    pub fn remove(&mut self, position: Point2<usize>, direction: Direction4) -> bool {
+        self.index(position, direction)
+            .is_some_and(|i| self.bits.remove(i))
+    }
+
+    pub fn contains(&self, position: Point2<usize>, direction: Direction4) -> bool {
+        self.index(position, direction)
+            .is_some_and(|i| self.bits.contains(i))
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    // Number of states, not positions.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<usize>, Direction4)> + '_ {
+        self.bits.iter().map(|i| {
+            let cell = i / 4;
+            (
+                Point2::new(cell % self.width, cell / self.width),
+                Direction4::ALL[i % 4],
+            )
+        })
+    }
+
+    // Every position that was visited in any direction.
+    pub fn positions(&self) -> BitGrid {
+        let mut grid = BitGrid::new(self.width, self.height);
+        grid.extend(self.iter().map(|(position, _)| position));
+        grid
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.bits.union_with(&other.bits);
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.bits.intersect_with(&other.bits);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.bits.difference_with(&other.bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BitSet::new(200);
+        assert!(set.insert(3));
+        assert!(set.insert(64));
+        assert!(set.insert(199));
+        assert!(!set.insert(64));
+        assert!(set.contains(199));
+        assert!(!set.contains(200));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 64, 199]);
+        assert!(set.remove(3));
+        assert!(!set.remove(3));
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.capacity(), 200);
+    }
+
+    #[test]
+    fn test_bitset_algebra() {
+        let mut a = BitSet::new(100);
+        a.extend([1, 2, 3, 70]);
+        let mut b = BitSet::new(100);
+        b.extend([3, 4, 70, 99]);
+
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(union.iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 70, 99]);
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(intersection.iter().collect::<Vec<_>>(), vec![3, 70]);
+        let mut difference = a.clone();
+        difference.difference_with(&b);
+        assert_eq!(difference.iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert!(intersection.is_subset(&a) && intersection.is_subset(&b));
+        assert!(!a.is_subset(&b));
+    }
+
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::new(5, 3);
+        assert!(grid.insert(Point2::new(4, 0)));
+        assert!(grid.insert(Point2::new(0, 2)));
+        assert!(!grid.insert(Point2::new(4, 0)));
+        assert!(grid.contains(Point2::new(0, 2)));
+        assert!(!grid.contains(Point2::new(5, 0)));
+        assert_eq!(
+            grid.iter().collect::<Vec<_>>(),
+            vec![Point2::new(4, 0), Point2::new(0, 2)]
+        );
+        let mut other = BitGrid::new(5, 3);
+        other.insert(Point2::new(2, 2));
+        grid.union_with(&other);
+        assert_eq!(grid.len(), 3);
+        assert!(grid.remove(Point2::new(2, 2)));
+        assert!(!grid.remove(Point2::new(9, 9)));
+    }
+
+    #[test]
+    fn test_state_bit_set() {
+        let mut states = StateBitSet::new(4, 4);
+        assert!(states.insert(Point2::new(1, 2), Direction4::East));
+        assert!(states.insert(Point2::new(1, 2), Direction4::North));
+        assert!(!states.insert(Point2::new(1, 2), Direction4::East));
+        assert!(states.insert(Point2::new(3, 3), Direction4::West));
+        assert!(states.contains(Point2::new(1, 2), Direction4::North));
+        assert!(!states.contains(Point2::new(1, 2), Direction4::South));
+        assert!(!states.contains(Point2::new(4, 2), Direction4::South));
+        assert_eq!(states.len(), 3);
+        assert_eq!(states.positions().len(), 2);
+        assert_eq!(
+            states.iter().collect::<Vec<_>>(),
+            vec![
+                (Point2::new(1, 2), Direction4::North),
+                (Point2::new(1, 2), Direction4::East),
+                (Point2::new(3, 3), Direction4::West)
+            ]
+        );
+        states.clear();
+        assert!(states.is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+use std::error::Error;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, Div, Mul, Sub};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+impl Error for Overflow {}
+
+pub trait CheckedOps: Copy {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_ops {
+    ($($t:ty),*) => {$(
+        impl CheckedOps for $t {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+        }
+    )*};
+}
+
+impl_checked_ops!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// Number that remembers if any operation leading to it overflowed (or divided by zero),
+// regardless of build profile. get() turns that into an error instead of a wrong answer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Checked<T>(Option<T>);
+
+impl<T: CheckedOps> Checked<T> {
+    pub fn new(value: T) -> Self {
+        Checked(Some(value))
+    }
+
+    pub fn overflow() -> Self {
+        Checked(None)
+    }
+
+    pub fn get(self) -> Result<T, Overflow> {
+        self.0.ok_or(Overflow)
+    }
+
+    pub fn is_overflow(self) -> bool {
+        self.0.is_none()
+    }
+
+    // The value, or max if anything overflowed. Only makes sense if nothing can go below min.
+    pub fn saturate(self, max: T) -> T {
+        self.0.unwrap_or(max)
+    }
+
+    fn apply(self, rhs: Self, op: fn(T, T) -> Option<T>) -> Self {
+        Checked(self.0.zip(rhs.0).and_then(|(a, b)| op(a, b)))
+    }
+}
+
+impl<T: CheckedOps> From<T> for Checked<T> {
+    fn from(value: T) -> Self {
+        Checked::new(value)
+    }
+}
+
+impl<T: CheckedOps + fmt::Display> fmt::Display for Checked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, "overflow"),
+        }
+    }
+}
+
+macro_rules! impl_checked_operator {
+    ($trait:ident, $method:ident, $checked:ident) => {
+        impl<T: CheckedOps> $trait for Checked<T> {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                self.apply(rhs, T::$checked)
+            }
+        }
+
+        impl<T: CheckedOps> $trait<T> for Checked<T> {
+            type Output = Self;
+
+            fn $method(self, rhs: T) -> Self {
+                self.apply(Checked::new(rhs), T::$checked)
+            }
+        }
+    };
+}
+
+impl_checked_operator!(Add, add, checked_add);
+impl_checked_operator!(Sub, sub, checked_sub);
+impl_checked_operator!(Mul, mul, checked_mul);
+impl_checked_operator!(Div, div, checked_div);
+
+impl<T: CheckedOps + Default> Sum for Checked<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Checked::new(T::default()), |a, b| a + b)
+    }
+}
+
+impl<T: CheckedOps + From<u8>> Product for Checked<T> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Checked::new(T::from(1)), |a, b| a * b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked() {
+        let a = Checked::new(200u8);
+        assert_eq!((a + 55).get(), Ok(255));
+        assert_eq!((a + 56).get(), Err(Overflow));
+        // Stays overflowed even if later operations would bring it back into range
+        assert!((a * 2u8 - 200).is_overflow());
+        assert_eq!((a / 0).get(), Err(Overflow));
+        assert!((Checked::overflow() + 1u8).is_overflow());
+        assert_eq!((Checked::new(5i32) - 10).get(), Ok(-5));
+        assert_eq!((a + 100).saturate(u8::MAX), 255);
+        assert_eq!(format!("{} {}", a, a * a), "200 overflow");
+    }
+
+    #[test]
+    fn test_sum_and_product() {
+        let sum: Checked<u64> = [u64::MAX - 1, 1].into_iter().map(Checked::new).sum();
+        assert_eq!(sum.get(), Ok(u64::MAX));
+        let sum: Checked<u64> = [u64::MAX, 1].into_iter().map(Checked::new).sum();
+        assert!(sum.is_overflow());
+        let product: Checked<u32> = (1..=12).map(Checked::new).product();
+        assert_eq!(product.get(), Ok(479001600));
+        let product: Checked<u32> = (1..=13).map(Checked::new).product();
+        assert!(product.is_overflow());
+
+        let result: Result<u8, Box<dyn Error>> = (|| Ok((Checked::new(255u8) + 1).get()?))();
+        assert_eq!(result.unwrap_err().to_string(), "arithmetic overflow");
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt::{self, Debug};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+type Implementation<'a, I, O> = (&'static str, Box<dyn Fn(&I) -> O + 'a>);
+
+// Implementations of the same function, e.g. a slow but obvious one and a fast one, that are
+// checked against each other and timed side by side. The first one is the reference.
+pub struct Alternatives<'a, I, O> {
+    implementations: Vec<Implementation<'a, I, O>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch<O> {
+    pub input: String,
+    pub reference: (&'static str, O),
+    pub other: (&'static str, O),
+}
+
+impl<O: Debug> fmt::Display for Mismatch<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} returned {:?} but {} returned {:?} for input {}",
+            self.other.0, self.other.1, self.reference.0, self.reference.1, self.input
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    pub name: &'static str,
+    pub median: Duration,
+    pub min: Duration,
+}
+
+impl<I, O> Default for Alternatives<'_, I, O> {
+    fn default() -> Self {
+        Alternatives {
+            implementations: vec![],
+        }
+    }
+}
+
+impl<'a, I: Debug, O: PartialEq + Debug> Alternatives<'a, I, O> {
+    pub fn new() -> Self {
+        Alternatives::default()
+    }
+
+    pub fn add(mut self, name: &'static str, implementation: impl Fn(&I) -> O + 'a) -> Self {
+        self.implementations.push((name, Box::new(implementation)));
+        self
+    }
+
+    // The common result, or the first implementation that disagrees with the reference.
+    pub fn check(&self, input: &I) -> Result<O, Mismatch<O>> {
+        let mut results = self
+            .implementations
+            .iter()
+            .map(|(name, f)| (*name, f(input)));
+        let reference = results.next().expect("no implementations");
+        match results.find(|(_, result)| *result != reference.1) {
+            Some(other) => Err(Mismatch {
+                input: format!("{:?}", input),
+                reference,
+                other,
+            }),
+            None => Ok(reference.1),
+        }
+    }
+
+    pub fn check_all<'b>(&self, inputs: impl IntoIterator<Item = &'b I>) -> Result<(), Mismatch<O>>
+    where
+        I: 'b,
+    {
+        inputs
+            .into_iter()
+            .try_for_each(|input| self.check(input).map(|_| ()))
+    }
+
+    // Runs every implementation the given number of times on the same input.
+    pub fn bench(&self, input: &I, runs: usize) -> Vec<Timing> {
+        self.implementations
+            .iter()
+            .map(|(name, f)| {
+                let mut durations = (0..runs.max(1))
+                    .map(|_| {
+                        let start = Instant::now();
+                        black_box(f(black_box(input)));
+                        start.elapsed()
+                    })
+                    .collect::<Vec<_>>();
+                durations.sort_unstable();
+                Timing {
+                    name,
+                    median: durations[durations.len() / 2],
+                    min: durations[0],
+                }
+            })
+            .collect()
+    }
+}
+
+// Table of the timings, with the median relative to the fastest one.
+pub fn report(timings: &[Timing]) -> String {
+    let fastest = timings
+        .iter()
+        .map(|t| t.median)
+        .min()
+        .unwrap_or_default()
+        .max(Duration::from_nanos(1));
+    let width = timings.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    timings
+        .iter()
+        .map(|t| {
+            format!(
+                "{:width$}  median {:>12?}  min {:>12?}  {:>6.2}x\n",
+                t.name,
+                t.median,
+                t.min,
+                t.median.as_secs_f64() / fastest.as_secs_f64(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum_loop(n: &u64) -> u64 {
+        (1..=*n).sum()
+    }
+
+    fn sum_formula(n: &u64) -> u64 {
+        n * (n + 1) / 2
+    }
+
+    #[test]
+    fn test_check() {
+        let sums = Alternatives::new()
+            .add("loop", sum_loop)
+            .add("formula", sum_formula);
+        assert_eq!(sums.check(&10), Ok(55));
+        assert_eq!(sums.check_all(&[0, 1, 7, 1000]), Ok(()));
+
+        let sums = sums.add("broken", |n| sum_formula(n) + (n / 5));
+        assert_eq!(
+            sums.check_all(&[0, 1, 7, 1000]).map_err(|e| e.input),
+            Err("7".to_string())
+        );
+        assert_eq!(
+            sums.check(&5).unwrap_err().to_string(),
+            "broken returned 16 but loop returned 15 for input 5"
+        );
+    }
+
+    #[test]
+    fn test_bench() {
+        let borrowed = vec![3u64; 1000];
+        let sums = Alternatives::new()
+            .add("iter", |v: &Vec<u64>| v.iter().sum::<u64>())
+            .add("borrowed", |v| v.len() as u64 * borrowed[0]);
+        let timings = sums.bench(&vec![3; 1000], 5);
+        assert_eq!(timings.len(), 2);
+        assert!(timings.iter().all(|t| t.min <= t.median));
+        let table = report(&timings);
+        assert_eq!(table.lines().count(), 2);
+        assert!(table.starts_with("iter    "));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::number_theory::lcm;
+
+// The sequence s_0, s_1, ... runs through `start` states once and then repeats
+// the next `length` states forever.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // The earliest step that has the same state as step n.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    // First step whose state was already seen before.
+    pub fn first_repeat(&self) -> usize {
+        self.start + self.length
+    }
+
+    // Cycle of a system made of independently evolving components,
+    // e.g. the x, y and z axes of a simulation.
+    pub fn combine<I: IntoIterator<Item = Cycle>>(cycles: I) -> Cycle {
+        cycles.into_iter().fold(
+            Cycle {
+                start: 0,
+                length: 1,
+            },
+            |combined, c| Cycle {
+                start: combined.start.max(c.start),
+                length: lcm(combined.length, c.length),
+            },
+        )
+    }
+}
+
+// Brent's algorithm, only keeps two states around. Never returns if the states never repeat.
+pub fn find_cycle<S, F>(initial: &S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    match try_find_cycle(initial, |s| Some(step(s))) {
+        Some(cycle) => cycle,
+        None => unreachable!("step always returns a state"),
+    }
+}
+
+// Like find_cycle, but the sequence ends (without a cycle) once step returns None.
+pub fn try_find_cycle<S, F>(initial: &S, mut step: F) -> Option<Cycle>
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> Option<S>,
+{
+    // Find the length: move the tortoise to the hare after every power of two steps
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    // Find the start: with the hare `length` steps ahead they meet at the first repeated state
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+// The state after n steps, without simulating more than start + length steps.
+pub fn state_at<S, F>(initial: S, mut step: F, n: usize) -> S
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let cycle = find_cycle(&initial, &mut step);
+    (0..cycle.reduce(n)).fold(initial, |s, _| step(&s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ...
+    fn rho(s: &u32) -> u32 {
+        if *s == 5 {
+            2
+        } else {
+            s + 1
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        assert_eq!(
+            find_cycle(&0, rho),
+            Cycle {
+                start: 2,
+                length: 4
+            }
+        );
+        assert_eq!(
+            find_cycle(&3, rho),
+            Cycle {
+                start: 0,
+                length: 4
+            }
+        );
+        assert_eq!(
+            find_cycle(&7, |_| 7),
+            Cycle {
+                start: 0,
+                length: 1
+            }
+        );
+        assert_eq!(find_cycle(&0, rho).first_repeat(), 6);
+    }
+
+    #[test]
+    fn test_try_find_cycle() {
+        assert_eq!(try_find_cycle(&0, |s| (*s < 10).then_some(s + 1)), None);
+        assert_eq!(
+            try_find_cycle(&0, |s| Some(rho(s))),
+            Some(find_cycle(&0, rho))
+        );
+    }
+
+    #[test]
+    fn test_state_at() {
+        assert_eq!(state_at(0, rho, 1), 1);
+        assert_eq!(state_at(0, rho, 6), 2);
+        assert_eq!(state_at(0, rho, 1_000_000_000_000), 4);
+        // Pseudo random sequence, compare against plain simulation
+        let lcg = |s: &u64| (s * 1103515245 + 12345) % 1000;
+        let simulated = (0..5000).fold(42, |s, _| lcg(&s));
+        assert_eq!(state_at(42, lcg, 5000), simulated);
+    }
+
+    #[test]
+    fn test_combine() {
+        let x = Cycle {
+            start: 0,
+            length: 4,
+        };
+        let y = Cycle {
+            start: 3,
+            length: 6,
+        };
+        assert_eq!(
+            Cycle::combine([x, y]),
+            Cycle {
+                start: 3,
+                length: 12
+            }
+        );
+        assert_eq!(Cycle::combine([x, y]).reduce(20), 8);
+    }
+}
@@ -0,0 +1,267 @@
+use std::env;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+// Small xorshift generator, enough to pick mutations reproducibly without a dependency.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9e3779b97f4a7c15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // Uniform enough in 0..n, n must not be 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+// Characters that often mean something to a parser, plus some that never should.
+const NOISE: &[&str] = &[
+    "0",
+    "1",
+    "9",
+    "-",
+    "+",
+    " ",
+    ",",
+    ":",
+    ".",
+    "#",
+    "\n",
+    "\n\n",
+    "a",
+    "Z",
+    "é",
+    "\t",
+    "99999999999999999999999",
+    "-1",
+    "0x",
+];
+
+// One random change to the input: truncating, deleting, duplicating or swapping lines,
+// or deleting, replacing and inserting characters and numbers.
+pub fn mutate(input: &str, rng: &mut Rng) -> String {
+    let mut lines = input.split_inclusive('\n').collect::<Vec<_>>();
+    let boundaries = input
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([input.len()])
+        .collect::<Vec<_>>();
+    let at = boundaries[rng.below(boundaries.len())];
+    let noise = NOISE[rng.below(NOISE.len())];
+    match rng.below(8) {
+        0 => input[..at].to_string(),
+        1 if !lines.is_empty() => {
+            lines.remove(rng.below(lines.len()));
+            lines.concat()
+        }
+        2 if !lines.is_empty() => {
+            let i = rng.below(lines.len());
+            lines.insert(i, lines[i]);
+            lines.concat()
+        }
+        3 if !lines.is_empty() => {
+            let (i, j) = (rng.below(lines.len()), rng.below(lines.len()));
+            lines.swap(i, j);
+            lines.concat()
+        }
+        4 if at < input.len() => {
+            let end = at + input[at..].chars().next().map_or(0, char::len_utf8);
+            format!("{}{}", &input[..at], &input[end..])
+        }
+        5 if at < input.len() => {
+            let end = at + input[at..].chars().next().map_or(0, char::len_utf8);
+            format!("{}{}{}", &input[..at], noise, &input[end..])
+        }
+        6 => {
+            // Blow up or zero a number, those tend to hit fixed size arrays and overflows
+            let digits = input[at..]
+                .find(|c: char| c.is_ascii_digit())
+                .map(|i| at + i);
+            match digits {
+                Some(start) => {
+                    let end = input[start..]
+                        .find(|c: char| !c.is_ascii_digit())
+                        .map_or(input.len(), |i| start + i);
+                    let replacement =
+                        ["0", "1", "100", "4294967296", "99999999999999999999"][rng.below(5)];
+                    format!("{}{}{}", &input[..start], replacement, &input[end..])
+                }
+                None => format!("{}{}{}", &input[..at], noise, &input[at..]),
+            }
+        }
+        _ => format!("{}{}{}", &input[..at], noise, &input[at..]),
+    }
+}
+
+// The given number of inputs, each the result of 1 to 4 mutations of a random example.
+// Empty input and a lone line break come first.
+pub fn mutations(examples: &[String], count: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    let mut inputs = vec![String::new(), "\n".to_string()];
+    while inputs.len() < count && !examples.is_empty() {
+        let mut input = examples[rng.below(examples.len())].clone();
+        for _ in 0..=rng.below(4) {
+            input = mutate(&input, &mut rng);
+        }
+        inputs.push(input);
+    }
+    inputs.truncate(count);
+    inputs
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub input: String,
+    pub problem: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} for input {:?}", self.problem, self.input)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub cases: usize,
+    pub seed: u64,
+    pub timeout: Duration,
+}
+
+impl Settings {
+    // FUZZ_CASES and FUZZ_SEED override the defaults, e.g. for a longer run or to reproduce one.
+    pub fn from_env() -> Self {
+        let var = |name, default| {
+            env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        Settings {
+            cases: var("FUZZ_CASES", 200) as usize,
+            seed: var("FUZZ_SEED", 0),
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+// Feeds mutated examples to parse. Whatever it returns is fine, but it must neither panic nor
+// take longer than the timeout for a single input. A hanging parse is left running in the
+// background, it can't be stopped.
+pub fn check_parser<F, R>(examples: &[String], settings: &Settings, parse: F) -> Result<(), Failure>
+where
+    F: Fn(&str) -> R + Send + 'static,
+{
+    let inputs = mutations(examples, settings.cases, settings.seed);
+    let (tx, rx) = mpsc::channel();
+    let worker_inputs = inputs.clone();
+    thread::spawn(move || {
+        for input in &worker_inputs {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| drop(parse(input))));
+            let message = result.err().map(|payload| {
+                payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default()
+            });
+            if tx.send(message).is_err() {
+                return;
+            }
+        }
+    });
+
+    for input in &inputs {
+        match rx.recv_timeout(settings.timeout) {
+            Ok(None) => {}
+            Ok(Some(message)) => {
+                return Err(Failure {
+                    input: input.clone(),
+                    problem: format!("panicked with {:?}", message),
+                })
+            }
+            Err(_) => {
+                return Err(Failure {
+                    input: input.clone(),
+                    problem: format!("no result after {:?}", settings.timeout),
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> Settings {
+        Settings {
+            cases: 300,
+            seed: 7,
+            timeout: Duration::from_millis(500),
+        }
+    }
+
+    fn examples() -> Vec<String> {
+        vec!["1,2\n3,4\n".to_string(), "10,20\n".to_string()]
+    }
+
+    #[test]
+    fn test_mutations() {
+        let inputs = mutations(&examples(), 100, 1);
+        assert_eq!(inputs.len(), 100);
+        assert_eq!(inputs[..2], ["", "\n"]);
+        assert_eq!(inputs, mutations(&examples(), 100, 1));
+        assert_ne!(inputs, mutations(&examples(), 100, 2));
+        assert!(inputs.iter().any(|i| i.contains("99999999999999999999")));
+    }
+
+    #[test]
+    fn test_check_parser() {
+        let careful = |input: &str| -> Result<Vec<(u8, u8)>, String> {
+            input
+                .lines()
+                .map(|l| {
+                    let (a, b) = l.split_once(',').ok_or("no comma")?;
+                    Ok((
+                        a.parse().map_err(|_| "bad number")?,
+                        b.parse().map_err(|_| "bad number")?,
+                    ))
+                })
+                .collect()
+        };
+        assert_eq!(check_parser(&examples(), &settings(), careful), Ok(()));
+
+        let careless = |input: &str| {
+            input
+                .lines()
+                .map(|l| l.split(',').map(|n| n.parse::<u8>().unwrap()).sum::<u8>())
+                .collect::<Vec<_>>()
+        };
+        let failure = check_parser(&examples(), &settings(), careless).unwrap_err();
+        assert!(failure.problem.starts_with("panicked"));
+
+        let hanging = |input: &str| {
+            while input.is_empty() {
+                thread::sleep(Duration::from_millis(100));
+            }
+        };
+        let failure = check_parser(&examples(), &settings(), hanging).unwrap_err();
+        assert_eq!(failure.input, "");
+        assert_eq!(failure.problem, "no result after 500ms");
+    }
+}
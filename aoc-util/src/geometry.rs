@@ -0,0 +1,558 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+// Grids are read top to bottom, so y grows towards the south.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction4 {
+    pub const ALL: [Direction4; 4] = [
+        Direction4::North,
+        Direction4::East,
+        Direction4::South,
+        Direction4::West,
+    ];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction4::North => Direction4::West,
+            Direction4::East => Direction4::North,
+            Direction4::South => Direction4::East,
+            Direction4::West => Direction4::South,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction4::North => Direction4::East,
+            Direction4::East => Direction4::South,
+            Direction4::South => Direction4::West,
+            Direction4::West => Direction4::North,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction4::North => Direction4::South,
+            Direction4::East => Direction4::West,
+            Direction4::South => Direction4::North,
+            Direction4::West => Direction4::East,
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction4::North | Direction4::South)
+    }
+
+    pub fn delta<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Direction4::North => (0, -1),
+            Direction4::East => (1, 0),
+            Direction4::South => (0, 1),
+            Direction4::West => (-1, 0),
+        };
+        Point2::new(T::from(x), T::from(y))
+    }
+
+    // Accepts arrows (^>v<), compass letters (NESW) and UDLR.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' | 'N' | 'U' => Some(Direction4::North),
+            '>' | 'E' | 'R' => Some(Direction4::East),
+            'v' | 'S' | 'D' => Some(Direction4::South),
+            '<' | 'W' | 'L' => Some(Direction4::West),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    // The variants are declared in the order of ALL.
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    // Turns by 45 degrees.
+    pub fn turn_left(self) -> Self {
+        Direction8::ALL[(self.index() + 7) % 8]
+    }
+
+    // Turns by 45 degrees.
+    pub fn turn_right(self) -> Self {
+        Direction8::ALL[(self.index() + 1) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Direction8::ALL[(self.index() + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn delta<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        };
+        Point2::new(T::from(x), T::from(y))
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(direction: Direction4) -> Self {
+        match direction {
+            Direction4::North => Direction8::North,
+            Direction4::East => Direction8::East,
+            Direction4::South => Direction8::South,
+            Direction4::West => Direction8::West,
+        }
+    }
+}
+
+// y is the first field so that the derived ordering is row-major (reading order).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2<T = isize> {
+    pub y: T,
+    pub x: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { y, x }
+    }
+
+    pub fn from_xy((x, y): (T, T)) -> Self {
+        Point2 { y, x }
+    }
+
+    pub fn from_yx((y, x): (T, T)) -> Self {
+        Point2 { y, x }
+    }
+
+    pub fn yx(self) -> (T, T) {
+        (self.y, self.x)
+    }
+
+    pub fn xy(self) -> (T, T) {
+        (self.x, self.y)
+    }
+
+    // Converts between coordinate types, e.g. isize <-> usize. None if any coordinate doesn't fit.
+    pub fn checked_cast<U: TryFrom<T>>(self) -> Option<Point2<U>> {
+        Some(Point2 {
+            y: U::try_from(self.y).ok()?,
+            x: U::try_from(self.x).ok()?,
+        })
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Point2 {
+            y: self.y + rhs.y,
+            x: self.x + rhs.x,
+        }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Point2 {
+            y: self.y - rhs.y,
+            x: self.x - rhs.x,
+        }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.y += rhs.y;
+        self.x += rhs.x;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.y -= rhs.y;
+        self.x -= rhs.x;
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Point2 {
+            y: self.y * rhs,
+            x: self.x * rhs,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point2 {
+            y: -self.y,
+            x: -self.x,
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T = isize> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn checked_cast<U: TryFrom<T>>(self) -> Option<Point3<U>> {
+        Some(Point3 {
+            x: U::try_from(self.x).ok()?,
+            y: U::try_from(self.y).ok()?,
+            z: U::try_from(self.z).ok()?,
+        })
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Point3 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Point3 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Point3 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point3 {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+macro_rules! impl_distances {
+    ($($t:ty => $u:ty),*) => {$(
+        impl Point2<$t> {
+            pub fn manhattan(self, other: Self) -> $u {
+                self.y.abs_diff(other.y) + self.x.abs_diff(other.x)
+            }
+
+            pub fn chebyshev(self, other: Self) -> $u {
+                self.y.abs_diff(other.y).max(self.x.abs_diff(other.x))
+            }
+        }
+
+        impl Point3<$t> {
+            pub fn manhattan(self, other: Self) -> $u {
+                self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+            }
+
+            pub fn chebyshev(self, other: Self) -> $u {
+                self.x
+                    .abs_diff(other.x)
+                    .max(self.y.abs_diff(other.y))
+                    .max(self.z.abs_diff(other.z))
+            }
+
+            pub fn distance_squared(self, other: Self) -> $u {
+                let x = self.x.abs_diff(other.x);
+                let y = self.y.abs_diff(other.y);
+                let z = self.z.abs_diff(other.z);
+                x * x + y * y + z * z
+            }
+        }
+    )*};
+}
+
+impl_distances!(isize => usize, i64 => u64, i32 => u32, usize => usize, u64 => u64, u32 => u32);
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl Point2<$t> {
+            pub fn step(self, direction: Direction4) -> Self {
+                self + direction.delta()
+            }
+
+            pub fn step8(self, direction: Direction8) -> Self {
+                self + direction.delta()
+            }
+
+            pub fn neighbours4(self) -> [Self; 4] {
+                Direction4::ALL.map(|d| self.step(d))
+            }
+
+            pub fn neighbours8(self) -> [Self; 8] {
+                Direction8::ALL.map(|d| self.step8(d))
+            }
+
+            // Rotates by 90 degrees around the origin, as seen on the screen.
+            pub fn rotate_left(self) -> Self {
+                Point2 {
+                    y: -self.x,
+                    x: self.y,
+                }
+            }
+
+            pub fn rotate_right(self) -> Self {
+                Point2 {
+                    y: self.x,
+                    x: -self.y,
+                }
+            }
+
+            // Checked conversion into an index of a grid with the given dimensions.
+            pub fn in_bounds(self, width: usize, height: usize) -> Option<Point2<usize>> {
+                self.checked_cast::<usize>()
+                    .filter(|p| p.x < width && p.y < height)
+            }
+        }
+
+        impl Point3<$t> {
+            pub fn neighbours6(self) -> [Self; 6] {
+                [
+                    Point3::new(self.x - 1, self.y, self.z),
+                    Point3::new(self.x + 1, self.y, self.z),
+                    Point3::new(self.x, self.y - 1, self.z),
+                    Point3::new(self.x, self.y + 1, self.z),
+                    Point3::new(self.x, self.y, self.z - 1),
+                    Point3::new(self.x, self.y, self.z + 1),
+                ]
+            }
+        }
+    )*};
+}
+
+impl_signed!(isize, i64, i32);
+
+macro_rules! impl_unsigned {
+    ($($t:ty => $s:ty),*) => {$(
+        impl Point2<$t> {
+            // None when stepping below zero.
+            pub fn checked_step(self, direction: Direction4) -> Option<Self> {
+                let delta = direction.delta::<$s>();
+                Some(Point2 {
+                    y: self.y.checked_add_signed(delta.y)?,
+                    x: self.x.checked_add_signed(delta.x)?,
+                })
+            }
+
+            pub fn checked_step8(self, direction: Direction8) -> Option<Self> {
+                let delta = direction.delta::<$s>();
+                Some(Point2 {
+                    y: self.y.checked_add_signed(delta.y)?,
+                    x: self.x.checked_add_signed(delta.x)?,
+                })
+            }
+
+            pub fn neighbours4(self) -> impl Iterator<Item = Self> {
+                Direction4::ALL
+                    .into_iter()
+                    .filter_map(move |d| self.checked_step(d))
+            }
+
+            pub fn neighbours8(self) -> impl Iterator<Item = Self> {
+                Direction8::ALL
+                    .into_iter()
+                    .filter_map(move |d| self.checked_step8(d))
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(usize => isize, u64 => i64, u32 => i32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction4_turns() {
+        for d in Direction4::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_right(), d.opposite());
+            assert_eq!(d.delta::<isize>() + d.opposite().delta(), Point2::new(0, 0));
+        }
+        assert_eq!(Direction4::North.turn_right(), Direction4::East);
+        assert_eq!(Direction4::from_char('v'), Some(Direction4::South));
+        assert_eq!(Direction4::from_char('L'), Some(Direction4::West));
+        assert_eq!(Direction4::from_char('x'), None);
+    }
+
+    #[test]
+    fn test_direction8_turns() {
+        assert_eq!(Direction8::North.turn_right(), Direction8::NorthEast);
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::SouthWest.opposite(), Direction8::NorthEast);
+        assert!(Direction8::SouthWest.is_diagonal());
+        for (i, direction) in Direction8::ALL.into_iter().enumerate() {
+            assert_eq!(direction.index(), i);
+        }
+        assert_eq!(
+            Direction8::from(Direction4::West).delta::<i32>(),
+            Direction4::West.delta()
+        );
+    }
+
+    #[test]
+    fn test_rotation_matches_turns() {
+        for d in Direction4::ALL {
+            assert_eq!(d.delta::<isize>().rotate_right(), d.turn_right().delta());
+            assert_eq!(d.delta::<isize>().rotate_left(), d.turn_left().delta());
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_and_distances() {
+        let a = Point2::new(1isize, -2);
+        let b = Point2::new(-3, 4);
+        assert_eq!(a + b, Point2::new(-2, 2));
+        assert_eq!(a - b, Point2::new(4, -6));
+        assert_eq!(a * 3, Point2::new(3, -6));
+        assert_eq!(-a, Point2::new(-1, 2));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+
+        let p = Point3::new(1i64, 2, 3);
+        let q = Point3::from([4, 0, 3]);
+        assert_eq!(p.manhattan(q), 5);
+        assert_eq!(p.chebyshev(q), 3);
+        assert_eq!(p.distance_squared(q), 13);
+        assert_eq!(p.neighbours6().len(), 6);
+    }
+
+    #[test]
+    fn test_ordering_is_row_major() {
+        let mut points = vec![Point2::new(0, 1), Point2::new(1, 0), Point2::new(0, 0)];
+        points.sort();
+        assert_eq!(
+            points,
+            vec![Point2::new(0, 0), Point2::new(1, 0), Point2::new(0, 1)]
+        );
+    }
+
+    #[test]
+    fn test_checked_conversions() {
+        assert_eq!(
+            Point2::new(2isize, 3).checked_cast::<usize>(),
+            Some(Point2::new(2usize, 3))
+        );
+        assert_eq!(Point2::new(-1isize, 3).checked_cast::<usize>(), None);
+        assert_eq!(Point2::new(3isize, 1).in_bounds(3, 3), None);
+        assert_eq!(
+            Point2::new(2isize, 1).in_bounds(3, 3),
+            Some(Point2::new(2, 1))
+        );
+        assert_eq!(Point2::from_yx((5usize, 7)).xy(), (7, 5));
+        assert_eq!(Point2::from_xy((5usize, 7)).yx(), (7, 5));
+
+        let origin = Point2::new(0usize, 0);
+        assert_eq!(origin.checked_step(Direction4::North), None);
+        assert_eq!(
+            origin.checked_step(Direction4::South),
+            Some(Point2::new(0, 1))
+        );
+        assert_eq!(origin.neighbours4().count(), 2);
+        assert_eq!(origin.neighbours8().count(), 3);
+        assert_eq!(Point2::new(5isize, 5).neighbours8().len(), 8);
+    }
+}
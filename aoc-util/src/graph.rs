@@ -0,0 +1,450 @@
+use crate::number_theory::Integer;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+// Result of a single source (or multi source) shortest path search. Keeps every predecessor
+// that lies on a shortest path, so all optimal paths can be reconstructed, not just one.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<N, C> {
+    distances: HashMap<N, C>,
+    predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N: Clone + Eq + Hash, C: Integer> ShortestPaths<N, C> {
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<N, C> {
+        &self.distances
+    }
+
+    // Empty for the start nodes.
+    pub fn predecessors(&self, node: &N) -> &[N] {
+        self.predecessors.get(node).map_or(&[], |p| p.as_slice())
+    }
+
+    // The reached node with the lowest distance that satisfies the predicate.
+    pub fn closest<F: FnMut(&N) -> bool>(&self, mut predicate: F) -> Option<(&N, C)> {
+        self.distances
+            .iter()
+            .filter(|(n, _)| predicate(n))
+            .min_by_key(|(_, &c)| c)
+            .map(|(n, &c)| (n, c))
+    }
+
+    // One shortest path from a start node to target, both included. The first predecessor of
+    // a node is the one that settled it, so following those always ends at a start node.
+    pub fn path_to(&self, target: &N) -> Option<Vec<N>> {
+        self.distances.get(target)?;
+        let mut path = vec![target.clone()];
+        while let Some(previous) = self.predecessors(path.last()?).first() {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    // Every node that lies on at least one shortest path to one of the targets.
+    // Only targets sharing the lowest distance count, the others are not optimal.
+    pub fn nodes_on_shortest_paths<'a, I>(&self, targets: I) -> HashSet<N>
+    where
+        I: IntoIterator<Item = &'a N>,
+        N: 'a,
+    {
+        let reached = targets
+            .into_iter()
+            .filter_map(|t| Some((t, self.distance(t)?)))
+            .collect::<Vec<_>>();
+        let Some(best) = reached.iter().map(|(_, c)| *c).min() else {
+            return HashSet::new();
+        };
+
+        let mut seen = HashSet::new();
+        let mut stack = reached
+            .into_iter()
+            .filter(|(_, c)| *c == best)
+            .map(|(t, _)| t.clone())
+            .collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            if seen.insert(node.clone()) {
+                stack.extend(self.predecessors(&node).iter().cloned());
+            }
+        }
+        seen
+    }
+
+    // Number of distinct shortest paths to target. Zero cost cycles would make it infinite.
+    pub fn count_paths_to(&self, target: &N) -> usize {
+        fn count<N: Clone + Eq + Hash>(
+            node: &N,
+            predecessors: &HashMap<N, Vec<N>>,
+            cache: &mut HashMap<N, usize>,
+        ) -> usize {
+            if let Some(&c) = cache.get(node) {
+                return c;
+            }
+            let c = match predecessors.get(node) {
+                Some(p) if !p.is_empty() => p.iter().map(|p| count(p, predecessors, cache)).sum(),
+                _ => 1,
+            };
+            cache.insert(node.clone(), c);
+            c
+        }
+
+        if !self.distances.contains_key(target) {
+            return 0;
+        }
+        count(target, &self.predecessors, &mut HashMap::new())
+    }
+}
+
+// Monotone priority queue for small integer costs (Dial's algorithm): one bucket per distance,
+// popping never goes back to a lower distance than the last popped one.
+#[derive(Debug, Clone)]
+pub struct BucketQueue<T> {
+    buckets: VecDeque<Vec<T>>,
+    offset: usize,
+    len: usize,
+}
+
+impl<T> Default for BucketQueue<T> {
+    fn default() -> Self {
+        BucketQueue::new()
+    }
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        BucketQueue {
+            buckets: VecDeque::new(),
+            offset: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, priority: usize, item: T) {
+        assert!(
+            priority >= self.offset,
+            "priority below the last popped one"
+        );
+        let index = priority - self.offset;
+        if index >= self.buckets.len() {
+            self.buckets.resize_with(index + 1, Vec::new);
+        }
+        self.buckets[index].push(item);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        while let Some(bucket) = self.buckets.front_mut() {
+            if let Some(item) = bucket.pop() {
+                self.len -= 1;
+                return Some((self.offset, item));
+            }
+            self.buckets.pop_front();
+            self.offset += 1;
+        }
+        None
+    }
+}
+
+trait Frontier<N, C> {
+    fn push(&mut self, cost: C, node: N);
+    fn pop(&mut self) -> Option<(C, N)>;
+}
+
+impl<N: Ord, C: Ord> Frontier<N, C> for BinaryHeap<Reverse<(C, N)>> {
+    fn push(&mut self, cost: C, node: N) {
+        BinaryHeap::push(self, Reverse((cost, node)));
+    }
+
+    fn pop(&mut self) -> Option<(C, N)> {
+        BinaryHeap::pop(self).map(|Reverse(entry)| entry)
+    }
+}
+
+impl<N> Frontier<N, usize> for BucketQueue<N> {
+    fn push(&mut self, cost: usize, node: N) {
+        BucketQueue::push(self, cost, node);
+    }
+
+    fn pop(&mut self) -> Option<(usize, N)> {
+        BucketQueue::pop(self)
+    }
+}
+
+fn search<N, C, Q, S, I>(
+    mut frontier: Q,
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+) -> ShortestPaths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Integer,
+    Q: Frontier<N, C>,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut distances = HashMap::new();
+    let mut predecessors: HashMap<N, Vec<N>> = HashMap::new();
+    let mut start_nodes = HashSet::new();
+    for start in starts {
+        distances.insert(start.clone(), C::ZERO);
+        start_nodes.insert(start.clone());
+        frontier.push(C::ZERO, start);
+    }
+
+    let mut done = HashSet::new();
+    while let Some((cost, node)) = frontier.pop() {
+        if distances.get(&node).is_some_and(|&d| d < cost) || !done.insert(node.clone()) {
+            continue;
+        }
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            match distances.get(&next) {
+                // Start nodes keep no predecessors, even when a zero cost cycle leads back to them
+                Some(&d) if d < next_cost || start_nodes.contains(&next) => {}
+                Some(&d) if d == next_cost => {
+                    predecessors.entry(next).or_default().push(node.clone());
+                }
+                _ => {
+                    distances.insert(next.clone(), next_cost);
+                    predecessors.insert(next.clone(), vec![node.clone()]);
+                    frontier.push(next_cost, next);
+                }
+            }
+        }
+    }
+
+    ShortestPaths {
+        distances,
+        predecessors,
+    }
+}
+
+// Dijkstra from all start nodes until every reachable node is settled. Costs must not be negative.
+pub fn shortest_paths<N, C, S, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+) -> ShortestPaths<N, C>
+where
+    N: Clone + Eq + Hash + Ord,
+    C: Integer,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    search(BinaryHeap::new(), starts, successors)
+}
+
+// Same as shortest_paths, but with a bucket queue. Faster when the costs are small integers,
+// nodes don't need to be Ord.
+pub fn shortest_paths_bucketed<N, S, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+) -> ShortestPaths<N, usize>
+where
+    N: Clone + Eq + Hash,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    search(BucketQueue::new(), starts, successors)
+}
+
+// Breadth first search, every edge costs 1.
+pub fn bfs_distances<N, S, I>(start: N, mut successors: S) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node] + 1;
+        for next in successors(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+// The k cheapest walks from start to a goal, cheapest first. Walks may visit a node more than
+// once (every node is expanded at most k times), which is what a state space search wants.
+pub fn k_shortest_paths<N, C, S, I, G>(
+    start: N,
+    mut successors: S,
+    mut is_goal: G,
+    k: usize,
+) -> Vec<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash + Ord,
+    C: Integer,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    G: FnMut(&N) -> bool,
+{
+    let mut result = vec![];
+    let mut expanded: HashMap<N, usize> = HashMap::new();
+    let mut heap = BinaryHeap::from([Reverse((C::ZERO, vec![start]))]);
+
+    while let Some(Reverse((cost, path))) = heap.pop() {
+        if result.len() == k {
+            break;
+        }
+        let node = path.last().expect("paths are never empty");
+        let count = expanded.entry(node.clone()).or_default();
+        if *count == k {
+            continue;
+        }
+        *count += 1;
+
+        if is_goal(node) {
+            result.push((path.clone(), cost));
+            continue;
+        }
+        for (next, step) in successors(node) {
+            let mut next_path = path.clone();
+            next_path.push(next);
+            heap.push(Reverse((cost + step, next_path)));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -1-> b -1-> d, a -2-> c -0-> d, d -5-> e, a -9-> e
+    fn diamond(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 2), ('e', 9)],
+            'b' => vec![('d', 1)],
+            'c' => vec![('d', 0)],
+            'd' => vec![('e', 5)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let paths = shortest_paths(['a'], diamond);
+        assert_eq!(paths.distance(&'d'), Some(2));
+        assert_eq!(paths.distance(&'e'), Some(7));
+        assert_eq!(paths.distance(&'x'), None);
+        assert_eq!(paths.count_paths_to(&'e'), 2);
+        assert_eq!(paths.count_paths_to(&'a'), 1);
+        assert_eq!(
+            paths.nodes_on_shortest_paths(&['e']),
+            HashSet::from(['a', 'b', 'c', 'd', 'e'])
+        );
+        let path = paths.path_to(&'e').unwrap();
+        assert_eq!(
+            (path.first(), path.last(), path.len()),
+            (Some(&'a'), Some(&'e'), 4)
+        );
+        assert_eq!(paths.closest(|n| *n > 'c'), Some((&'d', 2)));
+    }
+
+    #[test]
+    fn test_path_to_with_zero_cost_cycle() {
+        // a and b reach each other for free, b and c too
+        let successors = |node: &char| match node {
+            'a' => vec![('b', 0)],
+            'b' => vec![('a', 0), ('c', 0), ('d', 1)],
+            'c' => vec![('b', 0)],
+            _ => vec![],
+        };
+        let paths = shortest_paths(['a'], successors);
+        assert_eq!(paths.predecessors(&'a'), &[]);
+        assert_eq!(paths.path_to(&'a'), Some(vec!['a']));
+        assert_eq!(paths.path_to(&'c'), Some(vec!['a', 'b', 'c']));
+        assert_eq!(paths.path_to(&'d'), Some(vec!['a', 'b', 'd']));
+
+        let paths = shortest_paths_bucketed(['a', 'c'], |node: &char| {
+            successors(node).into_iter().map(|(n, c)| (n, c as usize))
+        });
+        assert_eq!(paths.predecessors(&'c'), &[]);
+        let path = paths.path_to(&'d').unwrap();
+        assert!(
+            path == ['a', 'b', 'd'] || path == ['c', 'b', 'd'],
+            "{:?}",
+            path
+        );
+    }
+
+    #[test]
+    fn test_nodes_on_shortest_paths_only_best_targets() {
+        let paths = shortest_paths(['a'], diamond);
+        assert_eq!(
+            paths.nodes_on_shortest_paths(&['b', 'c']),
+            HashSet::from(['a', 'b'])
+        );
+    }
+
+    #[test]
+    fn test_bucketed_matches_heap() {
+        // Grid graph with varying small weights
+        let successors = |&(x, y): &(usize, usize)| {
+            [(x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| x < 20 && y < 20)
+                .map(move |(nx, ny)| ((nx, ny), (x * 7 + y * 3 + nx + ny) % 5))
+                .collect::<Vec<_>>()
+        };
+        let heap = shortest_paths([(0, 0)], successors);
+        let bucket = shortest_paths_bucketed([(0, 0)], successors);
+        assert_eq!(heap.distances(), bucket.distances());
+        assert_eq!(
+            heap.count_paths_to(&(19, 19)),
+            bucket.count_paths_to(&(19, 19))
+        );
+    }
+
+    #[test]
+    fn test_bucket_queue() {
+        let mut queue = BucketQueue::new();
+        queue.push(3, 'c');
+        queue.push(1, 'a');
+        queue.push(3, 'd');
+        assert_eq!(queue.pop(), Some((1, 'a')));
+        queue.push(2, 'b');
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.pop(), Some((2, 'b')));
+        assert_eq!(queue.pop().map(|(p, _)| p), Some(3));
+        assert_eq!(queue.pop().map(|(p, _)| p), Some(3));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_bfs_distances() {
+        let distances = bfs_distances(0, |&n: &i32| {
+            [n - 1, n + 3].into_iter().filter(|n| n.abs() < 10)
+        });
+        assert_eq!(distances[&0], 0);
+        assert_eq!(distances[&2], 2);
+        assert_eq!(distances[&-2], 2);
+        assert_eq!(distances[&5], 3);
+    }
+
+    #[test]
+    fn test_k_shortest_paths() {
+        let paths = k_shortest_paths('a', diamond, |n| *n == 'e', 4);
+        let costs = paths.iter().map(|(_, c)| *c).collect::<Vec<_>>();
+        assert_eq!(costs, vec![7, 7, 9]);
+        assert_eq!(paths[2].0, vec!['a', 'e']);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::checked::Overflow;
+use crate::number_theory::Integer;
+use std::ops::{Range, RangeInclusive};
+
+// Sorted, disjoint and non-adjacent half-open ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // Number of values in the set, not the number of ranges.
+    pub fn total_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |sum, r| sum + (r.end - r.start))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end - T::ONE)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+        // Everything in first..last overlaps or touches the new range
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        if first == last {
+            self.ranges.insert(first, range);
+            return;
+        }
+        let start = range.start.min(self.ranges[first].start);
+        let end = range.end.max(self.ranges[last - 1].end);
+        self.ranges.splice(first..last, [start..end]);
+    }
+
+    // The ranges are half-open, so a range ending at the maximum of T doesn't fit.
+    pub fn insert_inclusive(&mut self, range: RangeInclusive<T>) -> Result<(), Overflow> {
+        let end = range.end().checked_add(T::ONE).ok_or(Overflow)?;
+        self.insert(*range.start()..end);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first >= last {
+            return;
+        }
+        let mut remaining = Vec::with_capacity(2);
+        if self.ranges[first].start < range.start {
+            remaining.push(self.ranges[first].start..range.start);
+        }
+        if self.ranges[last - 1].end > range.end {
+            remaining.push(range.end..self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, remaining);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.insert(range.clone());
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.remove(range.clone());
+        }
+        result
+    }
+
+    // Moves every value inside a source range to destination + (value - source.start).
+    // Values outside of all source ranges stay where they are. If source ranges overlap,
+    // the first one wins.
+    pub fn map_piecewise<I>(&self, mappings: I) -> Self
+    where
+        I: IntoIterator<Item = (Range<T>, T)>,
+    {
+        let mut unmapped = self.clone();
+        let mut result = IntervalSet::new();
+        for (source, destination) in mappings {
+            let source = IntervalSet::from_iter([source]);
+            let Some(source_start) = source.min() else {
+                continue;
+            };
+            for r in unmapped.intersection(&source).ranges {
+                result.insert(
+                    destination + (r.start - source_start)..destination + (r.end - source_start),
+                );
+            }
+            unmapped = unmapped.difference(&source);
+        }
+        result.union(&unmapped)
+    }
+}
+
+impl<T: Integer> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Integer> Extend<Range<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = IntervalSet::new();
+        set.insert(10..14);
+        set.insert(3..6);
+        set.insert(16..21);
+        set.insert(12..19);
+        assert_eq!(set.ranges(), &[3..6, 10..21]);
+        // Adjacent ranges are merged as well
+        set.insert(6..8);
+        assert_eq!(set.ranges(), &[3..8, 10..21]);
+        set.insert(0..0);
+        assert_eq!(set.total_len(), 16);
+        assert_eq!((set.min(), set.max()), (Some(3), Some(20)));
+    }
+
+    #[test]
+    fn test_insert_inclusive() {
+        // 2025 day 5 example
+        let mut set = IntervalSet::new();
+        for r in [3..=5, 10..=14, 16..=20, 12..=18] {
+            set.insert_inclusive(r).unwrap();
+        }
+        assert_eq!(set.total_len(), 14usize);
+        assert!(set.contains(5));
+        assert!(!set.contains(8));
+        assert!(set.contains(20));
+        assert!(!set.contains(21));
+
+        assert_eq!(set.insert_inclusive(0..=usize::MAX), Err(Overflow));
+        assert_eq!(set.total_len(), 14);
+        set.insert_inclusive(0..=usize::MAX - 1).unwrap();
+        assert_eq!(set.max(), Some(usize::MAX - 1));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut set = IntervalSet::from_iter([0..10, 20..30]);
+        set.remove(5..25);
+        assert_eq!(set.ranges(), &[0..5, 25..30]);
+        set.remove(26..27);
+        assert_eq!(set.ranges(), &[0..5, 25..26, 27..30]);
+        set.remove(-5..100);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = IntervalSet::from_iter([0..10, 20..30]);
+        let b = IntervalSet::from_iter([5..25, 28..40]);
+        assert_eq!(a.union(&b).ranges(), &[0..40]);
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25, 28..30]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 25..28]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20, 30..40]);
+    }
+
+    #[test]
+    fn test_map_piecewise() {
+        // 2023 day 5 example, seed-to-soil map on the seed ranges
+        let seeds = IntervalSet::from_iter([79usize..93, 55..68]);
+        let soil = seeds.map_piecewise([(98..100, 50), (50..98, 52)]);
+        assert_eq!(soil.ranges(), &[57..70, 81..95]);
+
+        let partially = IntervalSet::from_iter([0usize..10]);
+        assert_eq!(
+            partially.map_piecewise([(5..8, 100)]).ranges(),
+            &[0..5, 8..10, 100..103]
+        );
+        // First matching mapping wins
+        assert_eq!(
+            partially.map_piecewise([(0..5, 20), (3..10, 50)]).ranges(),
+            &[20..25, 52..57]
+        );
+    }
+}
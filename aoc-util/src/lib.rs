@@ -0,0 +1,16 @@
+pub mod bitset;
+pub mod checked;
+pub mod compare;
+pub mod cycle;
+pub mod fuzz;
+pub mod geometry;
+pub mod graph;
+pub mod interval;
+pub mod linear_system;
+pub mod memo;
+pub mod number_theory;
+pub mod parse;
+pub mod polygon;
+pub mod rational;
+pub mod spatial;
+pub mod union_find;
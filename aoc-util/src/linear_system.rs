@@ -0,0 +1,262 @@
+use crate::number_theory::extended_gcd;
+use crate::rational::Rational;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    None,
+    // Every solution is particular + sum(t_i * kernel[i]) for arbitrary t_i.
+    Infinite {
+        particular: Vec<Rational>,
+        kernel: Vec<Vec<Rational>>,
+    },
+}
+
+// Solves a * x = b exactly by Gauss-Jordan elimination over the rationals.
+// a has one row per equation and one column per variable; it doesn't need to be square.
+pub fn solve(a: &[Vec<i128>], b: &[i128]) -> Solution {
+    assert_eq!(a.len(), b.len(), "one right-hand side per equation");
+    let variables = a.first().map_or(0, |row| row.len());
+
+    let mut m = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| {
+            assert_eq!(row.len(), variables, "all equations need the same width");
+            row.iter()
+                .chain([&rhs])
+                .map(|&v| Rational::from(v))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut pivot_columns = vec![];
+    let mut row = 0;
+    for column in 0..variables {
+        let Some(pivot) = (row..m.len()).find(|&r| !m[r][column].is_zero()) else {
+            continue;
+        };
+        m.swap(row, pivot);
+
+        let pivot_value = m[row][column];
+        for v in m[row].iter_mut() {
+            *v = *v / pivot_value;
+        }
+        let pivot_row = m[row].clone();
+        for (r, other) in m.iter_mut().enumerate() {
+            if r != row && !other[column].is_zero() {
+                let factor = other[column];
+                for (v, &p) in other.iter_mut().zip(&pivot_row) {
+                    *v = *v - factor * p;
+                }
+            }
+        }
+
+        pivot_columns.push(column);
+        row += 1;
+    }
+
+    // A row 0 = c with c != 0 left over
+    if m[row..].iter().any(|r| !r[variables].is_zero()) {
+        return Solution::None;
+    }
+
+    let mut particular = vec![Rational::ZERO; variables];
+    for (r, &column) in pivot_columns.iter().enumerate() {
+        particular[column] = m[r][variables];
+    }
+
+    if pivot_columns.len() == variables {
+        return Solution::Unique(particular);
+    }
+
+    let kernel = (0..variables)
+        .filter(|c| !pivot_columns.contains(c))
+        .map(|free| {
+            let mut v = vec![Rational::ZERO; variables];
+            v[free] = Rational::ONE;
+            for (r, &column) in pivot_columns.iter().enumerate() {
+                v[column] = -m[r][free];
+            }
+            v
+        })
+        .collect();
+
+    Solution::Infinite { particular, kernel }
+}
+
+// Finds non-negative integers (x, y) with a * (x, y) = b that minimise cost[0] * x + cost[1] * y.
+// Handles degenerate (collinear) systems by walking the line of integer solutions.
+// None if there is no such solution or the cost is unbounded below.
+pub fn cheapest_nonnegative_integer_solution(
+    a: [[i128; 2]; 2],
+    b: [i128; 2],
+    cost: [i128; 2],
+) -> Option<[i128; 2]> {
+    match solve(&[a[0].to_vec(), a[1].to_vec()], &b) {
+        Solution::None => None,
+        Solution::Unique(x) => {
+            let x = [x[0].to_integer()?, x[1].to_integer()?];
+            (x[0] >= 0 && x[1] >= 0).then_some(x)
+        }
+        Solution::Infinite { .. } => {
+            // Both equations describe the same line (or everything is zero), use one of them
+            let (row, rhs) = if a[0] != [0, 0] {
+                (a[0], b[0])
+            } else if a[1] != [0, 0] {
+                (a[1], b[1])
+            } else {
+                return (cost[0] >= 0 && cost[1] >= 0).then_some([0, 0]);
+            };
+            cheapest_on_line(row, rhs, cost)
+        }
+    }
+}
+
+// p * x + q * y = r with (p, q) != (0, 0)
+fn cheapest_on_line([p, q]: [i128; 2], r: i128, cost: [i128; 2]) -> Option<[i128; 2]> {
+    let (g, s, t) = extended_gcd(p, q);
+    if r % g != 0 {
+        return None;
+    }
+
+    // All integer solutions: (x0 + k * dx, y0 + k * dy)
+    let (x0, y0) = (s * (r / g), t * (r / g));
+    let (dx, dy) = (q / g, -p / g);
+
+    let mut k_min = None;
+    let mut k_max = None;
+    for (v0, d) in [(x0, dx), (y0, dy)] {
+        // v0 + k * d >= 0
+        match d.signum() {
+            1 => {
+                let lo = div_ceil(-v0, d);
+                k_min = Some(k_min.map_or(lo, |k: i128| k.max(lo)));
+            }
+            -1 => {
+                let hi = div_floor(v0, -d);
+                k_max = Some(k_max.map_or(hi, |k: i128| k.min(hi)));
+            }
+            _ if v0 < 0 => return None,
+            _ => {}
+        }
+    }
+    if let (Some(lo), Some(hi)) = (k_min, k_max) {
+        if lo > hi {
+            return None;
+        }
+    }
+
+    let slope = cost[0] * dx + cost[1] * dy;
+    let k = match slope.signum() {
+        1 => k_min?,
+        -1 => k_max?,
+        _ => k_min.or(k_max).unwrap_or(0),
+    };
+
+    Some([x0 + k * dx, y0 + k * dy])
+}
+
+fn div_floor(a: i128, b: i128) -> i128 {
+    let q = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+    -div_floor(-a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(n: i128) -> Rational {
+        Rational::from(n)
+    }
+
+    #[test]
+    fn test_unique() {
+        // 2024 day 13 example, first claw machine
+        assert_eq!(
+            solve(&[vec![94, 22], vec![34, 67]], &[8400, 5400]),
+            Solution::Unique(vec![r(80), r(40)])
+        );
+        assert_eq!(
+            solve(&[vec![2, 0, 0], vec![0, 3, 0], vec![1, 1, 1]], &[1, 1, 1]),
+            Solution::Unique(vec![
+                Rational::new(1, 2),
+                Rational::new(1, 3),
+                Rational::new(1, 6)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_none() {
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 7]), Solution::None);
+    }
+
+    #[test]
+    fn test_infinite() {
+        let Solution::Infinite { particular, kernel } = solve(&[vec![1, 2], vec![2, 4]], &[3, 6])
+        else {
+            panic!("expected infinite solutions");
+        };
+        assert_eq!(particular, vec![r(3), r(0)]);
+        assert_eq!(kernel, vec![vec![r(-2), r(1)]]);
+    }
+
+    #[test]
+    fn test_cheapest_unique() {
+        let cost = [3, 1];
+        assert_eq!(
+            cheapest_nonnegative_integer_solution([[94, 22], [34, 67]], [8400, 5400], cost),
+            Some([80, 40])
+        );
+        assert_eq!(
+            cheapest_nonnegative_integer_solution([[26, 67], [66, 21]], [12748, 12176], cost),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cheapest_collinear() {
+        // A moves (1, 1) for 3 tokens, B moves (2, 2) for 1 token: A only for the odd step
+        assert_eq!(
+            cheapest_nonnegative_integer_solution([[1, 2], [1, 2]], [7, 7], [3, 1]),
+            Some([1, 3])
+        );
+        // A moves (3, 3) for 1 token, B moves (2, 2) for 3 tokens: use as much A as possible
+        assert_eq!(
+            cheapest_nonnegative_integer_solution([[3, 2], [3, 2]], [12, 12], [1, 3]),
+            Some([4, 0])
+        );
+        // Not reachable with integer presses
+        assert_eq!(
+            cheapest_nonnegative_integer_solution([[2, 4], [2, 4]], [7, 7], [3, 1]),
+            None
+        );
+        // Inconsistent collinear equations
+        assert_eq!(
+            cheapest_nonnegative_integer_solution([[1, 2], [2, 4]], [7, 7], [3, 1]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cheapest_unbounded() {
+        // x - y = 0 has arbitrarily large solutions, a negative cost makes it unbounded
+        assert_eq!(
+            cheapest_nonnegative_integer_solution([[1, -1], [2, -2]], [0, 0], [-1, 0]),
+            None
+        );
+        assert_eq!(
+            cheapest_nonnegative_integer_solution([[1, -1], [2, -2]], [0, 0], [1, 1]),
+            Some([0, 0])
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl Stats {
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+// Cache for recursive functions. The function takes the memo as argument and passes it on:
+//
+//     fn count(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+//         memo.get_or_compute(n, |memo| if n < 2 { n } else { count(n - 1, memo) + count(n - 2, memo) })
+//     }
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: Stats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo {
+            cache: HashMap::new(),
+            stats: Stats::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo::default()
+    }
+
+    // compute gets the memo back so that it can recurse through it.
+    pub fn get_or_compute<F>(&mut self, key: K, compute: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.stats = Stats::default();
+    }
+}
+
+// Wraps a recursive function so that it doesn't need to know about the cache at all.
+// The function gets a callback for the recursive calls.
+pub struct Memoized<K, V, F> {
+    function: F,
+    memo: Memo<K, V>,
+}
+
+impl<K, V, F> Memoized<K, V, F>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(function: F) -> Self {
+        Memoized {
+            function,
+            memo: Memo::new(),
+        }
+    }
+
+    pub fn call(&mut self, key: K) -> V {
+        Self::call_with(&self.function, &mut self.memo, key)
+    }
+
+    fn call_with(function: &F, memo: &mut Memo<K, V>, key: K) -> V {
+        memo.get_or_compute(key.clone(), |memo| {
+            function(&mut |k| Self::call_with(function, memo, k), key)
+        })
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.memo.stats()
+    }
+}
+
+// Thread-safe variant, e.g. for a cache shared between rayon tasks. The lock is not held while
+// computing, so recursion works; two threads may compute the same key at the same time.
+#[derive(Debug, Default)]
+pub struct SyncMemo<K, V> {
+    cache: RwLock<HashMap<K, V>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl<K: Eq + Hash, V: Clone> SyncMemo<K, V> {
+    pub fn new() -> Self {
+        SyncMemo {
+            cache: RwLock::new(HashMap::new()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn get_or_compute<F>(&self, key: K, compute: F) -> V
+    where
+        F: FnOnce(&Self) -> V,
+    {
+        if let Some(value) = self.cache.read().expect("lock poisoned").get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return value.clone();
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = compute(self);
+        self.cache
+            .write()
+            .expect("lock poisoned")
+            .insert(key, value.clone());
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.read().expect("lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        memo.get_or_compute(n, |memo| {
+            if n < 2 {
+                n
+            } else {
+                fibonacci(n - 1, memo) + fibonacci(n - 2, memo)
+            }
+        })
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(fibonacci(90, &mut memo), 2880067194370816120);
+        assert_eq!(memo.len(), 91);
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 88,
+                misses: 91
+            }
+        );
+        assert_eq!(memo.get(&10), Some(&55));
+
+        fibonacci(90, &mut memo);
+        assert_eq!(memo.stats().hits, 89);
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), Stats::default());
+    }
+
+    #[test]
+    fn test_memoized() {
+        // Paths through a grid moving only right or down
+        let mut paths = Memoized::new(|recurse: &mut dyn FnMut((u32, u32)) -> u64, (x, y)| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                recurse((x - 1, y)) + recurse((x, y - 1))
+            }
+        });
+        assert_eq!(paths.call((16, 16)), 601080390);
+        assert_eq!(paths.stats().misses, 16 * 16 + 2 * 16);
+        assert_eq!(paths.call((2, 2)), 6);
+    }
+
+    fn sync_fibonacci(n: u64, memo: &SyncMemo<u64, u64>) -> u64 {
+        memo.get_or_compute(n, |memo| {
+            if n < 2 {
+                n
+            } else {
+                sync_fibonacci(n - 1, memo) + sync_fibonacci(n - 2, memo)
+            }
+        })
+    }
+
+    #[test]
+    fn test_sync_memo() {
+        let memo = SyncMemo::new();
+        thread::scope(|s| {
+            for n in [60, 70, 80, 90] {
+                let memo = &memo;
+                s.spawn(move || sync_fibonacci(n, memo));
+            }
+        });
+        assert_eq!(sync_fibonacci(90, &memo), 2880067194370816120);
+        assert_eq!(memo.len(), 91);
+        let stats = memo.stats();
+        assert!(stats.misses >= 91);
+        assert!(stats.hits > 0);
+        assert_eq!(
+            Stats { hits: 1, misses: 3 }.to_string(),
+            "1 hits, 3 misses (25.0% hit rate)"
+        );
+    }
+}
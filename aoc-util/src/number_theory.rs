@@ -0,0 +1,250 @@
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+pub trait Integer:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs_value(self) -> Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    (unsigned: $($u:ty),*; signed: $($s:ty),*) => {
+        $(
+            impl Integer for $u {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs_value(self) -> Self {
+                    self
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$u>::checked_add(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$u>::checked_mul(self, rhs)
+                }
+            }
+        )*
+        $(
+            impl Integer for $s {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs_value(self) -> Self {
+                    self.abs()
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$s>::checked_add(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$s>::checked_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(unsigned: u8, u16, u32, u64, u128, usize; signed: i8, i16, i32, i64, i128, isize);
+
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs_value(), b.abs_value());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// Divides before multiplying so that only the result itself has to fit into T.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    (a / gcd(a, b) * b).abs_value()
+}
+
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b).map(Integer::abs_value)
+}
+
+pub fn gcd_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+    values.into_iter().fold(T::ZERO, gcd)
+}
+
+pub fn lcm_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+    values.into_iter().fold(T::ONE, lcm)
+}
+
+// Returns (g, x, y) with a * x + b * y = g = gcd(a, b).
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+// (a * b) mod m without overflowing, even if a * b doesn't fit into an i128.
+pub fn mul_mod(a: i128, b: i128, m: i128) -> i128 {
+    let (mut a, mut b) = (a.rem_euclid(m), b.rem_euclid(m));
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    // Double and add, every intermediate value stays below 2 * m.
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+fn add_mod(a: i128, b: i128, m: i128) -> i128 {
+    // a and b are in 0..m, so a - (m - b) can't overflow
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+pub fn pow_mod(base: i128, exponent: u128, m: i128) -> i128 {
+    let mut base = base.rem_euclid(m);
+    let mut exponent = exponent;
+    let mut result = 1 % m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+    result
+}
+
+// Solves x ≡ r (mod m) for every (r, m). The moduli don't need to be coprime.
+// Returns the smallest non-negative x and the lcm of all moduli, or None if the
+// congruences contradict each other, a modulus isn't positive or the lcm doesn't fit into an i128.
+pub fn crt<I: IntoIterator<Item = (i128, i128)>>(congruences: I) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(r1, m1), (r2, m2)| {
+            if m2 <= 0 {
+                return None;
+            }
+            let (g, p, _) = extended_gcd(m1, m2);
+            let diff = r2 - r1;
+            if diff.rem_euclid(g) != 0 {
+                return None;
+            }
+
+            // x = r1 + m1 * k with k = (diff / g) * p mod (m2 / g)
+            let m2_g = m2 / g;
+            let lcm = m1.checked_mul(m2_g)?;
+            let k = mul_mod(diff / g, p, m2_g);
+            let x = (r1 + mul_mod(m1, k, lcm)).rem_euclid(lcm);
+            Some((x, lcm))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12usize, 18), 6);
+        assert_eq!(gcd(-12isize, 18), 6);
+        assert_eq!(gcd(0u32, 7), 7);
+        assert_eq!(lcm(4usize, 6), 12);
+        assert_eq!(lcm(0usize, 6), 0);
+        assert_eq!(gcd_all([12u64, 18, 30]), 6);
+        assert_eq!(lcm_all([2usize, 3, 4, 5]), 60);
+        assert_eq!(lcm_all(Vec::<usize>::new()), 1);
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (46, 240), (-240, 46), (17, 5), (0, 9)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_mul_pow_mod() {
+        let m = i128::MAX - 1;
+        let a = i128::MAX / 3;
+        // (m - 1)^2 ≡ 1 (mod m)
+        assert_eq!(mul_mod(m - 1, m - 1, m), 1);
+        assert_eq!(mul_mod(a, 3, m), (a * 3) % m);
+        assert_eq!(mul_mod(-2, 3, 7), 1);
+        assert_eq!(pow_mod(2, 10, 1000), 24);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+        // Fermat: a^(p-1) ≡ 1 (mod p) for the Mersenne prime 2^127 - 1
+        assert_eq!(pow_mod(3, (i128::MAX - 1) as u128, i128::MAX), 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Not coprime but consistent
+        assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+        // Not coprime and inconsistent
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(2, 3), (0, 0)]), None);
+        assert_eq!(crt([(1, -5)]), None);
+        // 2020 day 13 example: bus 7 at t, bus 13 at t + 1, ...
+        let buses = [(7, 0), (13, 1), (59, 4), (31, 6), (19, 7)];
+        assert_eq!(
+            crt(buses.map(|(id, offset)| (-offset, id))),
+            Some((1068781, 3162341))
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use crate::geometry::Point2;
+use nom::{
+    bytes::complete::{tag, take_till1, take_while1},
+    character::complete::{char, digit1, multispace0, one_of, space0},
+    combinator::{all_consuming, map, map_res, opt, recognize, rest},
+    multi::separated_list1,
+    sequence::{pair, separated_pair, terminated, tuple},
+    Finish, IResult, Offset,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+// Line and column are 1-based, columns count chars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    // rest has to be a suffix slice of input, e.g. what a nom parser left over.
+    pub fn at(input: &str, rest: &str, message: impl Into<String>) -> Self {
+        let before = &input[..input.offset(rest)];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    fn from_nom(input: &str, e: nom::error::Error<&str>) -> Self {
+        let near = e.input.lines().next().unwrap_or_default();
+        let message = if near.is_empty() {
+            format!("{} failed at end of line", e.code.description())
+        } else {
+            format!("{} failed near {:?}", e.code.description(), near)
+        };
+        ParseError::at(input, e.input, message)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+// Runs the parser on the whole input, only trailing whitespace may be left over.
+pub fn parse_all<'a, O, P>(input: &'a str, parser: P) -> Result<O, ParseError>
+where
+    P: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    parse_within(input, input, parser)
+}
+
+// Same as parse_all, but for a part of input so that errors point into the whole input.
+fn parse_within<'a, O, P>(input: &'a str, part: &'a str, parser: P) -> Result<O, ParseError>
+where
+    P: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    all_consuming(terminated(parser, multispace0))(part)
+        .finish()
+        .map(|(_, o)| o)
+        .map_err(|e| ParseError::from_nom(input, e))
+}
+
+// Runs the parser on every line, each line has to be consumed completely.
+pub fn parse_lines<'a, O, P>(input: &'a str, mut parser: P) -> Result<Vec<O>, ParseError>
+where
+    P: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    input
+        .trim_end()
+        .lines()
+        .map(|line| parse_within(input, line, &mut parser))
+        .collect()
+}
+
+// Blocks of lines separated by blank lines, without the line breaks at their end.
+pub fn blocks(input: &str) -> Vec<&str> {
+    let mut blocks = vec![];
+    let mut start = None;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(s) = start.take() {
+                blocks.push(input[s..offset].trim_end());
+            }
+        } else if start.is_none() {
+            start = Some(offset);
+        }
+        offset += line.len();
+    }
+    if let Some(s) = start {
+        blocks.push(input[s..].trim_end());
+    }
+    blocks
+}
+
+pub fn parse_blocks<'a, O, P>(input: &'a str, mut parser: P) -> Result<Vec<O>, ParseError>
+where
+    P: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    blocks(input)
+        .into_iter()
+        .map(|block| parse_within(input, block, &mut parser))
+        .collect()
+}
+
+// Optional sign followed by digits.
+pub fn integer<T: FromStr>(i: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(i)
+}
+
+// Every integer in the text, ignoring everything around them: "x=-3..5, y=2-4" gives -3, 5, 2, 4.
+// A minus only counts as sign if it doesn't follow a letter or digit, so ranges like 2-4 work.
+pub fn integers<T: FromStr>(text: &str) -> Result<Vec<T>, ParseError> {
+    let mut numbers = vec![];
+    let mut previous = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let signed = (c == '-' || c == '+')
+            && !previous.is_some_and(char::is_alphanumeric)
+            && chars.peek().is_some_and(|(_, d)| d.is_ascii_digit());
+        if c.is_ascii_digit() || signed {
+            let mut end = start + c.len_utf8();
+            previous = Some(c);
+            while let Some(&(i, d)) = chars.peek() {
+                if !d.is_ascii_digit() {
+                    break;
+                }
+                end = i + 1;
+                previous = Some(d);
+                chars.next();
+            }
+            let number = text[start..end]
+                .parse()
+                .map_err(|_| ParseError::at(text, &text[start..], "integer out of range"))?;
+            numbers.push(number);
+        } else {
+            previous = Some(c);
+        }
+    }
+    Ok(numbers)
+}
+
+// "key: value", the key is trimmed.
+pub fn key_value<'a, O, P>(value: P) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O)>
+where
+    P: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    separated_pair(
+        map(take_till1(|c| c == ':' || c == '\n'), str::trim),
+        pair(char(':'), space0),
+        value,
+    )
+}
+
+pub fn key_values(input: &str) -> Result<Vec<(&str, &str)>, ParseError> {
+    parse_lines(input, key_value(rest))
+}
+
+fn node(i: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| !c.is_whitespace() && !",->".contains(c))(i)
+}
+
+// "a -> b, c"
+pub fn adjacency_line(i: &str) -> IResult<&str, (&str, Vec<&str>)> {
+    separated_pair(
+        node,
+        tuple((space0, tag("->"), space0)),
+        separated_list1(pair(char(','), space0), node),
+    )(i)
+}
+
+pub fn adjacency(input: &str) -> Result<Vec<(&str, Vec<&str>)>, ParseError> {
+    parse_lines(input, adjacency_line)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    pub cells: Vec<Vec<T>>,
+    pub markers: HashMap<char, Point2<usize>>,
+}
+
+impl<T> Grid<T> {
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.first().map_or(0, |row| row.len())
+    }
+
+    pub fn get(&self, position: Point2<usize>) -> Option<&T> {
+        self.cells.get(position.y)?.get(position.x)
+    }
+
+    pub fn marker(&self, c: char) -> Option<Point2<usize>> {
+        self.markers.get(&c).copied()
+    }
+}
+
+// Rectangular grid of characters. Every char in markers may show up at most once and is
+// remembered with its position, cell still decides what ends up in the grid (e.g. S is open floor).
+pub fn parse_grid<T, F>(input: &str, markers: &[char], mut cell: F) -> Result<Grid<T>, ParseError>
+where
+    F: FnMut(char) -> Option<T>,
+{
+    let mut grid = Grid {
+        cells: vec![],
+        markers: HashMap::new(),
+    };
+    for (y, line) in input.trim_end().lines().enumerate() {
+        let mut row = vec![];
+        for (x, (i, c)) in line.char_indices().enumerate() {
+            if markers.contains(&c) && grid.markers.insert(c, Point2::new(x, y)).is_some() {
+                return Err(ParseError::at(input, &line[i..], format!("second {:?}", c)));
+            }
+            row.push(
+                cell(c).ok_or_else(|| {
+                    ParseError::at(input, &line[i..], format!("unexpected {:?}", c))
+                })?,
+            );
+        }
+        if y > 0 && row.len() != grid.width() {
+            return Err(ParseError::at(
+                input,
+                line,
+                format!("row has {} cells instead of {}", row.len(), grid.width()),
+            ));
+        }
+        grid.cells.push(row);
+    }
+    Ok(grid)
+}
+
+pub fn char_grid(input: &str, markers: &[char]) -> Result<Grid<char>, ParseError> {
+    parse_grid(input, markers, Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::character::complete::space1;
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(
+            integers::<i32>("Sensor at x=2, y=-18: closest beacon is at x=-2, y=15").unwrap(),
+            vec![2, -18, -2, 15]
+        );
+        assert_eq!(integers::<i64>("2-4,6-8").unwrap(), vec![2, 4, 6, 8]);
+        assert_eq!(
+            integers::<i64>("x=-3..5 +7 a-1").unwrap(),
+            vec![-3, 5, 7, 1]
+        );
+        assert_eq!(integers::<u8>("").unwrap(), vec![]);
+        let error = integers::<u8>("1 2\n 300").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = "a\nb\n\n\nc\r\n\r\nd\n";
+        assert_eq!(blocks(input), vec!["a\nb", "c", "d"]);
+        assert_eq!(blocks("\n\n"), Vec::<&str>::new());
+
+        let sums = parse_blocks("1\n2\n\n3", |i| {
+            map(separated_list1(char('\n'), integer::<u32>), |v| {
+                v.iter().sum::<u32>()
+            })(i)
+        });
+        assert_eq!(sums.unwrap(), vec![3, 3]);
+        let error = parse_blocks("1\n2\n\n3\nx", |i| {
+            separated_list1(char('\n'), integer::<u32>)(i)
+        })
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (5, 1));
+    }
+
+    #[test]
+    fn test_parse_lines_error_position() {
+        let error = parse_lines("1,2\n3,4\n5;6\n", |i| {
+            separated_pair(integer::<i32>, char(','), integer::<i32>)(i)
+        })
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (3, 2));
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 2: Char failed near \";6\""
+        );
+    }
+
+    #[test]
+    fn test_key_values() {
+        assert_eq!(
+            key_values("Time: 7 15\nDistance :9").unwrap(),
+            vec![("Time", "7 15"), ("Distance", "9")]
+        );
+        let error = key_values("a: 1\nb 2").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+
+        let (_, (key, numbers)) =
+            key_value(separated_list1(space1, integer::<u32>))("Game 1: 3 4").unwrap();
+        assert_eq!((key, numbers), ("Game 1", vec![3, 4]));
+    }
+
+    #[test]
+    fn test_adjacency() {
+        assert_eq!(
+            adjacency("broadcaster -> a, b\n%a->b\n&inv -> a").unwrap(),
+            vec![
+                ("broadcaster", vec!["a", "b"]),
+                ("%a", vec!["b"]),
+                ("&inv", vec!["a"])
+            ]
+        );
+        let error = adjacency("a -> b\nc => d").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = parse_grid("#S.\n.E#\n", &['S', 'E'], |c| match c {
+            '#' => Some(false),
+            '.' | 'S' | 'E' => Some(true),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.marker('S'), Some(Point2::new(1, 0)));
+        assert_eq!(grid.marker('E'), Some(Point2::new(1, 1)));
+        assert_eq!(grid.get(Point2::new(2, 1)), Some(&false));
+        assert_eq!(grid.marker('X'), None);
+
+        let error = char_grid("S..\n..S", &['S']).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        let error = char_grid("...\n..", &[]).unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.message.as_str()),
+            (2, 1, "row has 2 cells instead of 3")
+        );
+        let error = parse_grid("..\n.x", &[], |c| (c == '.').then_some(())).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+}
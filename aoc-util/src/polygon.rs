@@ -0,0 +1,240 @@
+use crate::geometry::Point2;
+use crate::number_theory::gcd;
+
+type Coordinate = fn(Point2) -> isize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+// Simple polygon on the integer lattice. The vertices are given in order (either orientation),
+// the last one is connected back to the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point2>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point2>) -> Self {
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point2] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point2, Point2)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(&a, &b)| (a, b))
+    }
+
+    // Shoelace formula. Twice the area so that it stays an integer,
+    // positive for counter-clockwise vertices (with y pointing up).
+    pub fn signed_double_area(&self) -> isize {
+        self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum()
+    }
+
+    pub fn double_area(&self) -> usize {
+        self.signed_double_area().unsigned_abs()
+    }
+
+    // Lattice points on the edges, including the vertices.
+    pub fn boundary_points(&self) -> usize {
+        self.edges()
+            .map(|(a, b)| gcd(a.x.abs_diff(b.x), a.y.abs_diff(b.y)))
+            .sum()
+    }
+
+    // Lattice points strictly inside, by Pick's theorem: A = I + B / 2 - 1
+    pub fn interior_points(&self) -> usize {
+        (self.double_area() + 2 - self.boundary_points()) / 2
+    }
+
+    // Interior and boundary lattice points together, e.g. all tiles of a dug out lagoon.
+    pub fn enclosed_points(&self) -> usize {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn locate(&self, point: Point2) -> Location {
+        self.locate_scaled(point, 1)
+    }
+
+    // Locates point / scale without leaving the integers, so that midpoints can be checked.
+    fn locate_scaled(&self, point: Point2, scale: isize) -> Location {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (a, b) = (a * scale, b * scale);
+            let cross = (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x);
+            if cross == 0
+                && point.x >= a.x.min(b.x)
+                && point.x <= a.x.max(b.x)
+                && point.y >= a.y.min(b.y)
+                && point.y <= a.y.max(b.y)
+            {
+                return Location::Boundary;
+            }
+            // Cast a ray towards +x and count the edges it crosses
+            if (a.y > point.y) != (b.y > point.y) && (cross > 0) == (b.y > a.y) {
+                inside = !inside;
+            }
+        }
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    // Whether the axis-aligned rectangle spanned by the two corners (including its border) lies
+    // within the polygon (including its border). Only for rectilinear polygons.
+    pub fn contains_rectangle(&self, corner_a: Point2, corner_b: Point2) -> bool {
+        let (x1, x2) = (corner_a.x.min(corner_b.x), corner_a.x.max(corner_b.x));
+        let (y1, y2) = (corner_a.y.min(corner_b.y), corner_a.y.max(corner_b.y));
+
+        if x1 == x2 || y1 == y2 {
+            return self.contains_segment(Point2::new(x1, y1), Point2::new(x2, y2));
+        }
+
+        // If no edge runs through the inner part of the rectangle,
+        // the inner part is either completely inside or completely outside.
+        let edge_crosses_inner_part = self.edges().any(|(a, b)| {
+            debug_assert!(a.x == b.x || a.y == b.y, "polygon is not rectilinear");
+            let (ex1, ex2) = (a.x.min(b.x), a.x.max(b.x));
+            let (ey1, ey2) = (a.y.min(b.y), a.y.max(b.y));
+            ex1.max(x1) < ex2.min(x2) && y1 < ey1 && ey1 < y2
+                || ey1.max(y1) < ey2.min(y2) && x1 < ex1 && ex1 < x2
+        });
+
+        !edge_crosses_inner_part
+            && self.locate_scaled(Point2::new(x1 + x2, y1 + y2), 2) != Location::Outside
+    }
+
+    // Splits the axis-aligned segment wherever an edge touches it and checks every piece.
+    fn contains_segment(&self, from: Point2, to: Point2) -> bool {
+        let horizontal = from.y == to.y;
+        let x_of: Coordinate = |p| p.x;
+        let y_of: Coordinate = |p| p.y;
+        let (along, across, start, end) = if horizontal {
+            (x_of, y_of, from.x, to.x)
+        } else {
+            (y_of, x_of, from.y, to.y)
+        };
+        let line = across(from);
+
+        let mut cuts = vec![start, end];
+        for (a, b) in self.edges() {
+            let (c1, c2) = (across(a).min(across(b)), across(a).max(across(b)));
+            if c1 <= line && line <= c2 {
+                // Rectilinear: either the edge crosses the line or it lies on it
+                for v in [along(a), along(b)] {
+                    if start < v && v < end {
+                        cuts.push(v);
+                    }
+                }
+            }
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let at = |v: isize, scale: isize| {
+            if horizontal {
+                Point2::new(v, line * scale)
+            } else {
+                Point2::new(line * scale, v)
+            }
+        };
+
+        cuts.iter()
+            .all(|&v| self.locate(at(v, 1)) != Location::Outside)
+            && cuts
+                .windows(2)
+                .all(|w| self.locate_scaled(at(w[0] + w[1], 2), 2) != Location::Outside)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(isize, isize)]) -> Polygon {
+        Polygon::new(vertices.iter().map(|&p| Point2::from_xy(p)).collect())
+    }
+
+    // 2025 day 9 example
+    fn tiles() -> Polygon {
+        polygon(&[
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ])
+    }
+
+    #[test]
+    fn test_area_and_pick() {
+        let square = polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+        assert_eq!(square.signed_double_area(), 32);
+        assert_eq!(square.boundary_points(), 16);
+        assert_eq!(square.interior_points(), 9);
+        assert_eq!(square.enclosed_points(), 25);
+
+        let triangle = polygon(&[(0, 0), (0, 3), (3, 0)]);
+        assert_eq!(triangle.signed_double_area(), -9);
+        assert_eq!(triangle.boundary_points(), 9);
+        assert_eq!(triangle.interior_points(), 1);
+    }
+
+    #[test]
+    fn test_locate() {
+        let triangle = polygon(&[(0, 0), (6, 0), (0, 6)]);
+        assert_eq!(triangle.locate(Point2::new(1, 1)), Location::Inside);
+        assert_eq!(triangle.locate(Point2::new(3, 3)), Location::Boundary);
+        assert_eq!(triangle.locate(Point2::new(0, 6)), Location::Boundary);
+        assert_eq!(triangle.locate(Point2::new(4, 4)), Location::Outside);
+        assert_eq!(triangle.locate(Point2::new(-1, 0)), Location::Outside);
+
+        let tiles = tiles();
+        assert_eq!(tiles.locate(Point2::new(8, 2)), Location::Inside);
+        assert_eq!(tiles.locate(Point2::new(3, 2)), Location::Outside);
+        assert_eq!(tiles.locate(Point2::new(10, 7)), Location::Boundary);
+    }
+
+    #[test]
+    fn test_contains_rectangle() {
+        let tiles = tiles();
+        assert!(tiles.contains_rectangle(Point2::new(9, 5), Point2::new(2, 3)));
+        assert!(tiles.contains_rectangle(Point2::new(7, 3), Point2::new(11, 1)));
+        assert!(tiles.contains_rectangle(Point2::new(9, 7), Point2::new(9, 5)));
+        assert!(!tiles.contains_rectangle(Point2::new(2, 5), Point2::new(11, 1)));
+        assert!(!tiles.contains_rectangle(Point2::new(7, 1), Point2::new(2, 5)));
+    }
+
+    #[test]
+    fn test_contains_degenerate_rectangle() {
+        // U shape, the segment between the tips leaves the polygon in the middle
+        let u = polygon(&[
+            (0, 0),
+            (2, 0),
+            (2, 4),
+            (4, 4),
+            (4, 0),
+            (6, 0),
+            (6, 6),
+            (0, 6),
+        ]);
+        assert!(!u.contains_rectangle(Point2::new(0, 0), Point2::new(6, 0)));
+        assert!(u.contains_rectangle(Point2::new(0, 4), Point2::new(6, 4)));
+        assert!(u.contains_rectangle(Point2::new(0, 6), Point2::new(6, 6)));
+        assert!(u.contains_rectangle(Point2::new(3, 4), Point2::new(3, 6)));
+        assert!(!u.contains_rectangle(Point2::new(3, 0), Point2::new(3, 6)));
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::BinaryHeap;
+
+// Exact for coordinate differences up to about 2^31 per axis.
+pub fn squared_distance<const D: usize>(a: &[i64; D], b: &[i64; D]) -> u64 {
+    a.iter().zip(b).map(|(p, q)| p.abs_diff(*q).pow(2)).sum()
+}
+
+// Balanced k-d tree over integer points, stored implicitly: the median of every index range is
+// the node, the halves left and right of it are the subtrees. Results refer to the indices of the
+// points that the tree was built from.
+#[derive(Debug, Clone)]
+pub struct KdTree<const D: usize> {
+    points: Vec<[i64; D]>,
+    order: Vec<usize>,
+}
+
+impl<const D: usize> KdTree<D> {
+    pub fn new(points: &[[i64; D]]) -> Self {
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        build(points, &mut order, 0);
+        KdTree {
+            points: points.to_vec(),
+            order,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn point(&self, index: usize) -> &[i64; D] {
+        &self.points[index]
+    }
+
+    // The k points closest to query as (index, squared distance), closest first.
+    // Ties are broken by index.
+    pub fn nearest(&self, query: &[i64; D], k: usize) -> Vec<(usize, u64)> {
+        if k == 0 {
+            return vec![];
+        }
+        let mut best = BinaryHeap::new();
+        self.visit_nearest(query, k, 0, self.order.len(), 0, &mut best);
+        let mut result = best.into_iter().map(|(d, i)| (i, d)).collect::<Vec<_>>();
+        result.sort_unstable_by_key(|&(i, d)| (d, i));
+        result
+    }
+
+    fn visit_nearest(
+        &self,
+        query: &[i64; D],
+        k: usize,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        best: &mut BinaryHeap<(u64, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let index = self.order[mid];
+        let point = &self.points[index];
+        best.push((squared_distance(query, point), index));
+        if best.len() > k {
+            best.pop();
+        }
+
+        let axis = depth % D;
+        let ((near_lo, near_hi), (far_lo, far_hi)) = if query[axis] < point[axis] {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.visit_nearest(query, k, near_lo, near_hi, depth + 1, best);
+        let plane = query[axis].abs_diff(point[axis]).pow(2);
+        if best.len() < k || best.peek().is_some_and(|&(d, _)| plane <= d) {
+            self.visit_nearest(query, k, far_lo, far_hi, depth + 1, best);
+        }
+    }
+
+    // Every point with a squared distance of at most radius_squared, closest first.
+    pub fn within_radius(&self, query: &[i64; D], radius_squared: u64) -> Vec<(usize, u64)> {
+        let mut result = vec![];
+        let mut stack = vec![(0, self.order.len(), 0)];
+        while let Some((lo, hi, depth)) = stack.pop() {
+            if lo >= hi {
+                continue;
+            }
+            let mid = lo + (hi - lo) / 2;
+            let index = self.order[mid];
+            let point = &self.points[index];
+            let distance = squared_distance(query, point);
+            if distance <= radius_squared {
+                result.push((index, distance));
+            }
+
+            let axis = depth % D;
+            let plane = query[axis].abs_diff(point[axis]).pow(2);
+            if query[axis] < point[axis] || plane <= radius_squared {
+                stack.push((lo, mid, depth + 1));
+            }
+            if query[axis] >= point[axis] || plane <= radius_squared {
+                stack.push((mid + 1, hi, depth + 1));
+            }
+        }
+        result.sort_unstable_by_key(|&(i, d)| (d, i));
+        result
+    }
+
+    // The k closest pairs of different points as (squared distance, i, j) with i < j,
+    // ordered by distance and then by indices.
+    pub fn closest_pairs(&self, k: usize) -> Vec<(u64, usize, usize)> {
+        let n = self.len();
+        if k == 0 || n < 2 {
+            return vec![];
+        }
+
+        // Start with a few neighbours per point, enough for k pairs if they are spread evenly
+        let m = (2 * k).div_ceil(n).clamp(1, n - 1);
+        let neighbours = (0..n)
+            .map(|i| self.nearest(&self.points[i], m + 1))
+            .collect::<Vec<_>>();
+        let mut pairs = pairs_from(&neighbours);
+
+        // A pair within the k-th distance can only be missing if one of its points has all its
+        // m neighbours within that distance, look at those points again.
+        let limit = if pairs.len() >= k {
+            pairs[k - 1].0
+        } else {
+            u64::MAX
+        };
+        let widened = (0..n)
+            .map(|i| match neighbours[i].last() {
+                Some(&(_, d)) if neighbours[i].len() == m + 1 && d <= limit => {
+                    self.within_radius(&self.points[i], limit)
+                }
+                _ => vec![],
+            })
+            .collect::<Vec<_>>();
+        pairs.extend(pairs_from(&widened));
+
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs.truncate(k);
+        pairs
+    }
+}
+
+// Sorted and deduplicated pairs from per point neighbour lists.
+fn pairs_from(neighbours: &[Vec<(usize, u64)>]) -> Vec<(u64, usize, usize)> {
+    let mut pairs = neighbours
+        .iter()
+        .enumerate()
+        .flat_map(|(i, list)| {
+            list.iter()
+                .filter(move |&&(j, _)| i != j)
+                .map(move |&(j, d)| (d, i.min(j), i.max(j)))
+        })
+        .collect::<Vec<_>>();
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+fn build<const D: usize>(points: &[[i64; D]], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+    let axis = depth % D;
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by_key(mid, |&i| points[i][axis]);
+    let (left, right) = order.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random_points<const D: usize>(n: usize) -> Vec<[i64; D]> {
+        let mut state = 12345u64;
+        (0..n)
+            .map(|_| {
+                [0; D].map(|_| {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    ((state >> 33) % 1000) as i64 - 500
+                })
+            })
+            .collect()
+    }
+
+    fn brute_force_pairs<const D: usize>(points: &[[i64; D]]) -> Vec<(u64, usize, usize)> {
+        let mut pairs = (0..points.len())
+            .flat_map(|i| {
+                (i + 1..points.len()).map(move |j| (squared_distance(&points[i], &points[j]), i, j))
+            })
+            .collect::<Vec<_>>();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    #[test]
+    fn test_nearest_and_radius() {
+        let points = [[0, 0], [10, 0], [0, 3], [4, 4], [-2, -2], [7, 1]];
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.nearest(&[1, 1], 3), vec![(0, 2), (2, 5), (3, 18)]);
+        assert_eq!(tree.nearest(&[9, 1], 1), vec![(1, 2)]);
+        assert_eq!(tree.nearest(&[0, 0], 0), vec![]);
+        assert_eq!(tree.nearest(&[0, 0], 10).len(), 6);
+        assert_eq!(tree.within_radius(&[5, 2], 9), vec![(3, 5), (5, 5)]);
+        assert_eq!(tree.point(3), &[4, 4]);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let points = pseudo_random_points::<3>(300);
+        let tree = KdTree::new(&points);
+        let query = [17, -40, 222];
+
+        let mut expected = (0..points.len())
+            .map(|i| (i, squared_distance(&query, &points[i])))
+            .collect::<Vec<_>>();
+        expected.sort_unstable_by_key(|&(i, d)| (d, i));
+        assert_eq!(tree.nearest(&query, 25), expected[..25]);
+        let radius = expected[40].1;
+        assert_eq!(
+            tree.within_radius(&query, radius),
+            expected
+                .iter()
+                .copied()
+                .filter(|&(_, d)| d <= radius)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_closest_pairs() {
+        let points = pseudo_random_points::<3>(200);
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.closest_pairs(150), brute_force_pairs(&points)[..150]);
+
+        // Lots of ties on a small grid
+        let grid = (0..5)
+            .flat_map(|x| (0..5).map(move |y| [x, y]))
+            .collect::<Vec<_>>();
+        let tree = KdTree::new(&grid);
+        assert_eq!(tree.closest_pairs(45), brute_force_pairs(&grid)[..45]);
+        assert_eq!(tree.closest_pairs(1000), brute_force_pairs(&grid));
+    }
+}
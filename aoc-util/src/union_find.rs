@@ -0,0 +1,134 @@
+// Disjoint sets over the elements 0..len, with path compression and union by size.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            components: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Point everything on the way directly to the root
+        let mut current = element;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    // False if both were already in the same component.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (large, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    // Size of the component that contains element.
+    pub fn component_size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.size[root]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    // One entry per component, in no particular order.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect()
+    }
+
+    // The k largest component sizes, largest first.
+    pub fn largest_component_sizes(&self, k: usize) -> Vec<usize> {
+        let mut sizes = self.component_sizes();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes.truncate(k);
+        sizes
+    }
+
+    // Unions the edges in order and returns the one that leaves a single component.
+    // None if the edges run out before that (or there was only one component to begin with).
+    pub fn union_until_connected<I>(&mut self, edges: I) -> Option<(usize, usize)>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        edges
+            .into_iter()
+            .find(|&(a, b)| self.union(a, b) && self.components == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(8);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.union(5, 6));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 5));
+        assert_eq!(sets.component_size(2), 4);
+        assert_eq!(sets.component_size(7), 1);
+        assert_eq!(sets.component_count(), 4);
+        assert_eq!(sets.largest_component_sizes(3), vec![4, 2, 1]);
+        let mut sizes = sets.component_sizes();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 1, 2, 4]);
+    }
+
+    #[test]
+    fn test_union_until_connected() {
+        let mut sets = UnionFind::new(4);
+        let edges = [(0, 1), (1, 0), (2, 3), (0, 3), (1, 2)];
+        assert_eq!(sets.union_until_connected(edges), Some((0, 3)));
+        assert_eq!(sets.component_count(), 1);
+
+        let mut sets = UnionFind::new(4);
+        assert_eq!(sets.union_until_connected([(0, 1), (2, 3)]), None);
+        assert_eq!(sets.component_count(), 2);
+    }
+}